@@ -0,0 +1,70 @@
+// In-memory cache for the list endpoints.
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+// `std::time::Instant` panics on wasm32-unknown-unknown.
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+use crate::{CFPError, Conf};
+
+type Slot = Arc<tokio::sync::Mutex<Option<(Instant, Vec<Conf>)>>>;
+
+pub(crate) struct Cache {
+    ttl: Duration,
+    slots: Mutex<HashMap<String, Slot>>,
+}
+
+impl Cache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Return the cached list for `key` if it's still fresh, otherwise call
+    // `fetch` and store its result.
+    pub(crate) async fn get_or_fetch<F, Fut>(
+        &self,
+        key: &str,
+        fetch: F,
+    ) -> Result<Vec<Conf>, CFPError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<Conf>, CFPError>>,
+    {
+        let slot = self
+            .slots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+
+        // Holding the slot across the fetch makes concurrent callers wait for
+        // the in-flight request instead of firing their own.
+        let mut entry = slot.lock().await;
+        if let Some((fetched_at, confs)) = entry.as_ref() {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(confs.clone());
+            }
+        }
+
+        let confs = fetch().await?;
+        *entry = Some((Instant::now(), confs.clone()));
+
+        Ok(confs)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.slots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
@@ -0,0 +1,89 @@
+//! Interface over the API endpoints, so code can accept a fake in tests.
+use async_trait::async_trait;
+
+use crate::{CFPError, CFPTime, Conf};
+
+/// The CFPTime API endpoints, implemented by [`CFPTime`].
+///
+/// Accept an `Arc<dyn CfpClient>` instead of a concrete [`CFPTime`] to swap in
+/// a fake in tests. The helper methods built on these endpoints, such as
+/// [`CFPTime::get_open_cfps`], are only available on [`CFPTime`].
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use async_trait::async_trait;
+/// use cfptime::{CFPError, CfpClient, Conf};
+///
+/// struct Fake;
+///
+/// #[async_trait]
+/// impl CfpClient for Fake {
+///     async fn get_cfps(&self) -> Result<Vec<Conf>, CFPError> {
+///         Ok(vec![Conf::new(5, "RustConf")])
+///     }
+///     async fn get_cfp(&self, cfp_id: i32) -> Result<Conf, CFPError> {
+///         Ok(Conf::new(cfp_id, "RustConf"))
+///     }
+///     async fn get_confs(&self) -> Result<Vec<Conf>, CFPError> {
+///         Ok(Vec::new())
+///     }
+///     async fn get_conf(&self, conf_id: i32) -> Result<Conf, CFPError> {
+///         Ok(Conf::new(conf_id, "RustConf"))
+///     }
+///     async fn get_upcoming(&self) -> Result<Vec<Conf>, CFPError> {
+///         Ok(Vec::new())
+///     }
+/// }
+///
+/// async fn count_cfps(client: Arc<dyn CfpClient>) -> usize {
+///     client.get_cfps().await.map_or(0, |cfps| cfps.len())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// assert_eq!(count_cfps(Arc::new(Fake)).await, 1);
+/// # }
+/// ```
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait CfpClient: Send + Sync {
+    /// See [`CFPTime::get_cfps`].
+    async fn get_cfps(&self) -> Result<Vec<Conf>, CFPError>;
+
+    /// See [`CFPTime::get_cfp`].
+    async fn get_cfp(&self, cfp_id: i32) -> Result<Conf, CFPError>;
+
+    /// See [`CFPTime::get_confs`].
+    async fn get_confs(&self) -> Result<Vec<Conf>, CFPError>;
+
+    /// See [`CFPTime::get_conf`].
+    async fn get_conf(&self, conf_id: i32) -> Result<Conf, CFPError>;
+
+    /// See [`CFPTime::get_upcoming`].
+    async fn get_upcoming(&self) -> Result<Vec<Conf>, CFPError>;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl CfpClient for CFPTime {
+    async fn get_cfps(&self) -> Result<Vec<Conf>, CFPError> {
+        CFPTime::get_cfps(self).await
+    }
+
+    async fn get_cfp(&self, cfp_id: i32) -> Result<Conf, CFPError> {
+        CFPTime::get_cfp(self, cfp_id).await
+    }
+
+    async fn get_confs(&self) -> Result<Vec<Conf>, CFPError> {
+        CFPTime::get_confs(self).await
+    }
+
+    async fn get_conf(&self, conf_id: i32) -> Result<Conf, CFPError> {
+        CFPTime::get_conf(self, conf_id).await
+    }
+
+    async fn get_upcoming(&self) -> Result<Vec<Conf>, CFPError> {
+        CFPTime::get_upcoming(self).await
+    }
+}
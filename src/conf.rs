@@ -0,0 +1,957 @@
+//! Conference records returned by the CFPTime API, and helpers for working
+//! with lists of them.
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc};
+use reqwest::Url;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "geojson")]
+mod geojson;
+#[cfg(feature = "ical")]
+mod ical;
+#[cfg(feature = "typed")]
+mod typed;
+mod validate;
+
+#[cfg(feature = "csv")]
+pub use self::csv::to_csv;
+#[cfg(feature = "geojson")]
+pub use geojson::to_geojson;
+#[cfg(feature = "ical")]
+pub use ical::to_ical;
+#[cfg(feature = "typed")]
+pub use typed::{ConfTyped, ConfTypedError};
+pub use validate::{validate, ConfProblem, ConfValidation};
+
+// Formats of timestamps without an offset, tried in order after RFC 3339,
+// e.g. `2023-08-28T14:23:43.456789` or `2023-08-28 14:23:43`.
+const DATETIME_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+// Formats of date-only values, tried in order after the timestamp formats,
+// e.g. `2023-09-30`, `2023/09/30`, `30.09.2023`, `September 30, 2023`,
+// `Sep 30, 2023` or `30 September 2023`.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y", "%B %d, %Y", "%b %d, %Y", "%d %B %Y", "%d %b %Y"];
+
+/// Days left at or below which [`Conf::cfp_status`] reports a CFP as
+/// [`CfpStatus::ClosingSoon`].
+pub const CLOSING_SOON_DAYS: i64 = 7;
+
+// Values used in free-text fields to mean "nothing", compared after `normalize`.
+const PLACEHOLDERS: &[&str] = &["-", "n/a", "na", "no", "none", "null", "tba", "tbd", "unknown"];
+
+/// A conference and its call for papers.
+///
+/// Equality, hashing and ordering all use `id` alone: two `Conf`s are equal
+/// when they describe the same conference, even if other fields changed
+/// between fetches, so a `HashSet<Conf>` or `BTreeSet<Conf>` holds one record
+/// per conference. Compare fields directly to detect changes, and use
+/// [`sort_by_deadline`] to sort by `cfp_deadline`.
+///
+/// Fields the API often leaves out (`city`, `province`, `twitter`, `website`,
+/// `cfp_details`, `speaker_benefits` and `code_of_conduct`) default to an
+/// empty string when missing:
+///
+/// ```
+/// use cfptime::CFPTime;
+///
+/// let confs = CFPTime::from_json_str(r#"[{
+///     "id": 5,
+///     "name": "RustConf",
+///     "cfp_deadline": "2030-04-01",
+///     "conf_start_date": "2030-09-10",
+///     "city": "Portland",
+///     "province": "OR",
+///     "country": "USA",
+///     "website": "https://rustconf.com",
+///     "cfp_details": "",
+///     "speaker_benefits": "",
+///     "code_of_conduct": "",
+///     "created_at": "2030-01-01T00:00:00Z",
+///     "number_of_days": 2
+/// }]"#).unwrap();
+///
+/// assert_eq!(confs[0].twitter, "");
+/// ```
+///
+/// `id` is accepted both as a number and as a numeric string, as found in
+/// some responses and in files cached by older versions:
+///
+/// ```
+/// use cfptime::Conf;
+///
+/// let json = r#"{"id": ID, "name": "RustConf", "cfp_deadline": "", "conf_start_date": "",
+///     "country": "", "created_at": "", "number_of_days": 1}"#;
+///
+/// assert_eq!(Conf::from_json(&json.replace("ID", "5")).unwrap().id, 5);
+/// assert_eq!(Conf::from_json(&json.replace("ID", r#""5""#)).unwrap().id, 5);
+/// assert!(Conf::from_json(&json.replace("ID", r#""five""#)).is_err());
+/// ```
+///
+/// New fields may be added as the API grows, so `Conf` can't be built with a
+/// struct literal outside this crate. Use [`Conf::new`] and set the remaining
+/// fields on the result instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Conf {
+    #[serde(deserialize_with = "deserialize_id")]
+    pub id: i32,
+    pub name: String,
+    pub cfp_deadline: String,
+    pub conf_start_date: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub province: String,
+    pub country: String,
+    #[serde(default)]
+    pub twitter: String,
+    #[serde(default)]
+    pub website: String,
+    #[serde(default)]
+    pub cfp_details: String,
+    #[serde(default)]
+    pub speaker_benefits: String,
+    #[serde(default)]
+    pub code_of_conduct: String,
+    pub created_at: String,
+    pub number_of_days: i32,
+}
+
+/// The writable fields of a conference, submitted with
+/// [`CFPTime::create_conf`](crate::CFPTime::create_conf).
+///
+/// The server assigns `id` and `created_at`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewConf {
+    pub name: String,
+    pub cfp_deadline: String,
+    pub conf_start_date: String,
+    pub city: String,
+    pub province: String,
+    pub country: String,
+    pub twitter: String,
+    pub website: String,
+    pub cfp_details: String,
+    pub speaker_benefits: String,
+    pub code_of_conduct: String,
+    pub number_of_days: i32,
+}
+
+impl Conf {
+    /// Create a conference with the given `id` and `name`, all other fields
+    /// empty and `number_of_days` zero.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(5, "RustConf");
+    /// conf.cfp_deadline = "2030-04-01".to_string();
+    /// conf.number_of_days = 2;
+    /// assert!(conf.duration().is_some());
+    /// ```
+    pub fn new(id: i32, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Parse `cfp_deadline` into a UTC timestamp.
+    ///
+    /// Accepts the formats listed on [`Conf::parse_date_field`]; a bare date
+    /// is taken to be midnight UTC, and a timestamp without an offset is taken
+    /// to be UTC. An empty or malformed value is returned as a
+    /// `chrono::ParseError` instead of panicking.
+    pub fn cfp_deadline_datetime(&self) -> Result<DateTime<Utc>, chrono::ParseError> {
+        parse_datetime(&self.cfp_deadline)
+    }
+
+    /// Parse `created_at` into a UTC timestamp.
+    ///
+    /// Accepts the same formats as [`Conf::cfp_deadline_datetime`], so the
+    /// full timestamps used here and the date-only values of the other date
+    /// fields are both handled.
+    pub fn created_at_datetime(&self) -> Result<DateTime<Utc>, chrono::ParseError> {
+        parse_datetime(&self.created_at)
+    }
+
+    /// Number of whole days from today until `cfp_deadline`.
+    ///
+    /// The comparison is date-only: both the deadline and `Utc::now()` are
+    /// truncated to their UTC calendar date, so a deadline later today is `0`.
+    /// Past deadlines are negative, and `None` is returned when the deadline
+    /// can't be parsed.
+    pub fn days_until_deadline(&self) -> Option<i64> {
+        let deadline = self.cfp_deadline_datetime().ok()?.date_naive();
+        let today = Utc::now().date_naive();
+        Some((deadline - today).num_days())
+    }
+
+    /// Whether the CFP is still accepting submissions, i.e. `cfp_deadline` is
+    /// today or later (see [`Conf::days_until_deadline`]).
+    ///
+    /// A deadline that can't be parsed is treated as closed and returns `false`.
+    /// The comparison uses UTC calendar dates, so a CFP with an "anywhere on
+    /// earth" deadline stays open for up to a day longer than this reports,
+    /// see [`Conf::deadline_aoe`] for the exact cut-off.
+    pub fn is_open(&self) -> bool {
+        matches!(self.days_until_deadline(), Some(days) if days >= 0)
+    }
+
+    /// Where the CFP stands, with the same date-only comparison as
+    /// [`Conf::days_until_deadline`].
+    ///
+    /// A CFP with at most [`CLOSING_SOON_DAYS`] days left, including one
+    /// closing today, is [`CfpStatus::ClosingSoon`]; use
+    /// [`Conf::cfp_status_within`] for another threshold.
+    ///
+    /// ```
+    /// use cfptime::conf::CfpStatus;
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(1, "RustConf");
+    /// conf.cfp_deadline = "2001-01-01".to_string();
+    /// assert_eq!(conf.cfp_status(), CfpStatus::Closed);
+    /// conf.cfp_deadline = "TBA".to_string();
+    /// assert_eq!(conf.cfp_status(), CfpStatus::Unknown);
+    /// ```
+    pub fn cfp_status(&self) -> CfpStatus {
+        self.cfp_status_within(CLOSING_SOON_DAYS)
+    }
+
+    /// Like [`Conf::cfp_status`], but a CFP is closing soon when it has at
+    /// most `closing_soon_days` days left.
+    pub fn cfp_status_within(&self, closing_soon_days: i64) -> CfpStatus {
+        match self.days_until_deadline() {
+            None => CfpStatus::Unknown,
+            Some(days_left) if days_left < 0 => CfpStatus::Closed,
+            Some(days_left) if days_left <= closing_soon_days => CfpStatus::ClosingSoon { days_left },
+            Some(days_left) => CfpStatus::Open { days_left },
+        }
+    }
+
+    /// Last moment to submit, taking the deadline date as "anywhere on earth"
+    /// (AoE).
+    ///
+    /// AoE is the usual convention for CFP deadlines: submissions are accepted
+    /// until the date has ended everywhere, i.e. until 23:59:59 in UTC-12,
+    /// which is 11:59:59 UTC the following day. Only the date of
+    /// `cfp_deadline` is used, see [`Conf::parse_date_field`]. Returns `None`
+    /// when it can't be parsed.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(1, "RustConf");
+    /// conf.cfp_deadline = "2030-04-01".to_string();
+    /// assert_eq!(conf.deadline_aoe().unwrap().to_rfc3339(), "2030-04-02T11:59:59+00:00");
+    /// ```
+    pub fn deadline_aoe(&self) -> Option<DateTime<Utc>> {
+        let aoe = FixedOffset::west_opt(12 * 60 * 60)?;
+        self.deadline_in(&aoe)
+    }
+
+    /// Last moment to submit, taking the deadline date as ending at 23:59:59
+    /// in `tz`, e.g. the conference's own timezone.
+    ///
+    /// Like [`Conf::deadline_aoe`] only the date of `cfp_deadline` is used.
+    /// Returns `None` when it can't be parsed; if that time is ambiguous in
+    /// `tz` because of a DST change, the later instant is used.
+    pub fn deadline_in<Tz: TimeZone>(&self, tz: &Tz) -> Option<DateTime<Utc>> {
+        let date = Self::parse_date_field(&self.cfp_deadline)?;
+        let end_of_day = date.and_hms_opt(23, 59, 59)?;
+
+        tz.from_local_datetime(&end_of_day)
+            .latest()
+            .map(|deadline| deadline.with_timezone(&Utc))
+    }
+
+    /// Last day of the conference, computed from `conf_start_date` and
+    /// `number_of_days`.
+    ///
+    /// Days are counted inclusively, so a one-day conference ends on its start
+    /// date. Returns `None` when the start date can't be parsed or
+    /// `number_of_days` isn't positive (see [`Conf::duration`]).
+    pub fn conf_end_date(&self) -> Option<NaiveDate> {
+        let duration = self.duration()?;
+
+        let start = self.start_date()?;
+        start.checked_add_signed(duration - chrono::Duration::days(1))
+    }
+
+    /// Length of the conference, `number_of_days` as a `chrono::Duration`.
+    ///
+    /// Returns `None` when `number_of_days` isn't positive, which the API
+    /// occasionally returns for incomplete records.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        if self.number_of_days <= 0 {
+            return None;
+        }
+
+        Some(chrono::Duration::days(i64::from(self.number_of_days)))
+    }
+
+    /// ISO 3166-1 alpha-2 code for `country`, e.g. `"DE"` for "Germany".
+    ///
+    /// Matches official and common short names, alpha-2/alpha-3 codes and
+    /// aliases such as "USA", "UK" or "South Korea", ignoring case, spaces and
+    /// punctuation. Returns `None` for unrecognized input. Requires the
+    /// `country-code` feature.
+    #[cfg(feature = "country-code")]
+    pub fn country_code(&self) -> Option<String> {
+        let key: String = self
+            .country
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        // "UK" isn't an ISO code, the United Kingdom is "GB".
+        let key = if key == "uk" { "gb" } else { key.as_str() };
+
+        key.parse::<celes::Country>()
+            .ok()
+            .map(|country| country.alpha2.to_string())
+    }
+
+    /// Normalized `website` URL.
+    ///
+    /// Surrounding whitespace is trimmed and `https://` is prepended when the
+    /// value has no scheme. Returns `None` when the result still isn't a valid URL.
+    ///
+    /// ```
+    /// # use cfptime::Conf;
+    /// # fn conf(website: &str) -> Conf {
+    /// #     let mut conf = Conf::new(1, "");
+    /// #     conf.website = website.to_string();
+    /// #     conf
+    /// # }
+    /// assert_eq!(conf("example.com").website_url().unwrap().as_str(), "https://example.com/");
+    /// assert_eq!(conf(" https://x.org ").website_url().unwrap().as_str(), "https://x.org/");
+    /// assert_eq!(conf("").website_url(), None);
+    /// ```
+    pub fn website_url(&self) -> Option<Url> {
+        let website = self.website.trim();
+        if website.is_empty() {
+            return None;
+        }
+
+        if website.contains("://") {
+            Url::parse(website).ok()
+        } else {
+            Url::parse(&format!("https://{}", website)).ok()
+        }
+    }
+
+    /// Bare Twitter handle from `twitter`, without `@` or URL parts.
+    ///
+    /// Accepts `@handle`, `handle` and profile URLs such as
+    /// `https://twitter.com/handle` or `x.com/handle`. Returns `None` when the
+    /// field is empty or doesn't contain a usable handle.
+    pub fn twitter_handle(&self) -> Option<String> {
+        let mut twitter = self.twitter.trim();
+        if let Some((_, rest)) = twitter.split_once("://") {
+            twitter = rest;
+        }
+        twitter = twitter.strip_prefix("www.").unwrap_or(twitter);
+        for host in ["twitter.com/", "x.com/"] {
+            if twitter.get(..host.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(host)) {
+                twitter = &twitter[host.len()..];
+            }
+        }
+
+        let handle = twitter
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches('@');
+        if handle.is_empty() || !handle.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        Some(handle.to_string())
+    }
+
+    /// Canonical `https://twitter.com/{handle}` profile URL, see [`Conf::twitter_handle`].
+    pub fn twitter_url(&self) -> Option<Url> {
+        let handle = self.twitter_handle()?;
+        Url::parse(&format!("https://twitter.com/{}", handle)).ok()
+    }
+
+    /// Whether the conference advertises a code of conduct.
+    ///
+    /// `code_of_conduct` has to be non-empty after trimming and not a
+    /// placeholder such as "N/A", "none" or "TBD" (ignoring case).
+    pub fn has_code_of_conduct(&self) -> bool {
+        !is_placeholder(&self.code_of_conduct)
+    }
+
+    /// Whether the conference mentions speaker benefits, i.e.
+    /// `speaker_benefits` isn't empty or a placeholder such as "N/A" or "none".
+    pub fn offers_speaker_benefits(&self) -> bool {
+        !is_placeholder(&self.speaker_benefits)
+    }
+
+    /// Individual benefits listed in `speaker_benefits`, e.g.
+    /// `["Travel", "Hotel", "Free ticket"]` for "Travel, Hotel; Free ticket".
+    ///
+    /// This is best-effort text splitting: the free-text field is split on
+    /// commas, semicolons and newlines, leading list bullets are removed,
+    /// entries are trimmed, and empty entries and placeholders are dropped.
+    /// Benefits that themselves contain a comma end up split.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(1, "RustConf");
+    /// conf.speaker_benefits = "Travel, Hotel;\n- Free ticket\n".to_string();
+    /// assert_eq!(conf.speaker_benefits_list(), ["Travel", "Hotel", "Free ticket"]);
+    /// ```
+    pub fn speaker_benefits_list(&self) -> Vec<String> {
+        self.speaker_benefits
+            .split([',', ';', '\n', '\r'])
+            .map(|benefit| benefit.trim().trim_start_matches(['-', '*', '•']).trim())
+            .filter(|benefit| !is_placeholder(benefit))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// `city`, `province` and `country` joined with `", "`, leaving out empty
+    /// parts so there are no stray commas. Each part is trimmed; an empty
+    /// string is returned when all three are empty.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(5, "RustConf");
+    /// conf.city = "Austin".to_string();
+    /// conf.province = "TX".to_string();
+    /// conf.country = "USA".to_string();
+    /// assert_eq!(conf.location(), "Austin, TX, USA");
+    ///
+    /// conf.city = "Berlin".to_string();
+    /// conf.province = " ".to_string();
+    /// conf.country = "Germany".to_string();
+    /// assert_eq!(conf.location(), "Berlin, Germany");
+    ///
+    /// conf.city.clear();
+    /// assert_eq!(conf.location(), "Germany");
+    /// ```
+    pub fn location(&self) -> String {
+        [self.city.trim(), self.province.trim(), self.country.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// One-line description for logs and CLI output, e.g.
+    /// `#5 RustConf — Portland, USA (CFP closes 2030-04-01, 10 days left)`.
+    ///
+    /// Empty location parts are left out, a past deadline reads
+    /// `(CFP closed 2023-04-01)` and a deadline that can't be parsed is shown
+    /// as-is, see [`Conf::days_until_deadline`].
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(5, "RustConf");
+    /// conf.city = "Portland".to_string();
+    /// conf.country = "USA".to_string();
+    /// conf.cfp_deadline = "2023-04-01".to_string();
+    /// assert_eq!(conf.summary(), "#5 RustConf — Portland, USA (CFP closed 2023-04-01)");
+    ///
+    /// conf.cfp_deadline = "TBA".to_string();
+    /// assert_eq!(conf.summary(), "#5 RustConf — Portland, USA (CFP closes TBA)");
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut summary = format!("#{} {}", self.id, self.name.trim());
+        let location = self.city_country();
+        if !location.is_empty() {
+            summary.push_str(" — ");
+            summary.push_str(&location);
+        }
+
+        let deadline = self.cfp_deadline.trim();
+        let status = match self.days_until_deadline() {
+            Some(days) if days < 0 => format!("CFP closed {}", deadline),
+            Some(0) => format!("CFP closes {}, today", deadline),
+            Some(1) => format!("CFP closes {}, 1 day left", deadline),
+            Some(days) => format!("CFP closes {}, {} days left", deadline, days),
+            None => format!("CFP closes {}", deadline),
+        };
+        summary.push_str(&format!(" ({})", status));
+
+        summary
+    }
+
+    /// Serialize this conference to a JSON object, e.g. to persist it to disk.
+    ///
+    /// The output uses the same field names as the API, so it can be read back
+    /// with [`Conf::from_json`] and round-trips every field exactly.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let conf = Conf::from_json(r#"{
+    ///     "id": 5,
+    ///     "name": "RustConf",
+    ///     "cfp_deadline": "2030-04-01",
+    ///     "conf_start_date": "2030-09-10",
+    ///     "country": "USA",
+    ///     "created_at": "2030-01-01T00:00:00Z",
+    ///     "number_of_days": 2
+    /// }"#).unwrap();
+    ///
+    /// let json = conf.to_json().unwrap();
+    /// assert_eq!(Conf::from_json(&json).unwrap().to_json().unwrap(), json);
+    /// ```
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Serialize the conference like [`Conf::to_json`], but with `cfp_deadline`,
+    /// `conf_start_date` and `created_at` rewritten as RFC 3339 UTC
+    /// timestamps, whatever format the API used.
+    ///
+    /// Dates are parsed as in [`Conf::cfp_deadline_datetime`], so a bare date
+    /// becomes midnight UTC. A field that can't be parsed, including an empty
+    /// one, is written unchanged rather than dropped or failing the whole
+    /// record.
+    ///
+    /// ```
+    /// use cfptime::Conf;
+    ///
+    /// let mut conf = Conf::new(5, "RustConf");
+    /// conf.cfp_deadline = "April 1, 2030".to_string();
+    /// conf.created_at = "2030-01-01T09:30:00+02:00".to_string();
+    /// conf.conf_start_date = "TBA".to_string();
+    ///
+    /// let json: serde_json::Value = serde_json::from_str(&conf.to_json_normalized().unwrap()).unwrap();
+    /// assert_eq!(json["cfp_deadline"], "2030-04-01T00:00:00Z");
+    /// assert_eq!(json["created_at"], "2030-01-01T07:30:00Z");
+    /// assert_eq!(json["conf_start_date"], "TBA");
+    /// ```
+    pub fn to_json_normalized(&self) -> Result<String, serde_json::Error> {
+        let mut conf = self.clone();
+        for field in [&mut conf.cfp_deadline, &mut conf.conf_start_date, &mut conf.created_at] {
+            if let Ok(datetime) = parse_datetime(field) {
+                *field = datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+            }
+        }
+
+        conf.to_json()
+    }
+
+    /// Deserialize a conference from a JSON object, such as one written by
+    /// [`Conf::to_json`] or a single record returned by the API.
+    pub fn from_json(json: &str) -> Result<Conf, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    // Fill the empty fields of this record from `other`, a record of the same
+    // conference from another endpoint.
+    pub(crate) fn fill_from(&mut self, other: &Conf) {
+        for (field, fallback) in [
+            (&mut self.name, &other.name),
+            (&mut self.cfp_deadline, &other.cfp_deadline),
+            (&mut self.conf_start_date, &other.conf_start_date),
+            (&mut self.city, &other.city),
+            (&mut self.province, &other.province),
+            (&mut self.country, &other.country),
+            (&mut self.twitter, &other.twitter),
+            (&mut self.website, &other.website),
+            (&mut self.cfp_details, &other.cfp_details),
+            (&mut self.speaker_benefits, &other.speaker_benefits),
+            (&mut self.code_of_conduct, &other.code_of_conduct),
+            (&mut self.created_at, &other.created_at),
+        ] {
+            if field.trim().is_empty() {
+                field.clone_from(fallback);
+            }
+        }
+        if self.number_of_days <= 0 {
+            self.number_of_days = other.number_of_days;
+        }
+    }
+
+    // "City, Country", leaving out empty parts.
+    fn city_country(&self) -> String {
+        [self.city.trim(), self.country.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Parse a date field such as `cfp_deadline` or `conf_start_date` into a
+    /// calendar date, returning `None` when no supported format matches.
+    ///
+    /// Formats are tried in this order, and the first match wins:
+    ///
+    /// 1. RFC 3339 timestamps with an offset, e.g. `2030-04-01T12:00:00+02:00`,
+    ///    converted to UTC before taking the date.
+    /// 2. Timestamps without an offset, e.g. `2030-04-01T12:00:00.123` or
+    ///    `2030-04-01 12:00:00`, taken to be UTC.
+    /// 3. ISO dates, `2030-04-01`, and `2030/04/01`.
+    /// 4. European dotted dates, `01.04.2030`.
+    /// 5. English month names, `April 1, 2030`, `Apr 1, 2030`, `1 April 2030`
+    ///    and `1 Apr 2030`.
+    ///
+    /// Slash-separated dates with the year last, such as `04/01/2030`, are
+    /// rejected because day and month order is ambiguous.
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use cfptime::Conf;
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2030, 4, 1);
+    /// for field in [
+    ///     "2030-04-01T12:00:00+02:00",
+    ///     "2030-04-01T12:00:00Z",
+    ///     "2030-04-01T12:00:00.123",
+    ///     "2030-04-01 12:00:00",
+    ///     "2030-04-01",
+    ///     "2030/04/01",
+    ///     "01.04.2030",
+    ///     "April 1, 2030",
+    ///     "Apr 1, 2030",
+    ///     "1 April 2030",
+    ///     " 1 Apr 2030 ",
+    /// ] {
+    ///     assert_eq!(Conf::parse_date_field(field), date, "{}", field);
+    /// }
+    ///
+    /// // Converted to UTC first.
+    /// assert_eq!(Conf::parse_date_field("2030-04-01T01:00:00+02:00"), NaiveDate::from_ymd_opt(2030, 3, 31));
+    /// assert_eq!(Conf::parse_date_field("04/01/2030"), None);
+    /// assert_eq!(Conf::parse_date_field(""), None);
+    /// ```
+    pub fn parse_date_field(field: &str) -> Option<NaiveDate> {
+        parse_datetime(field).ok().map(|datetime| datetime.date_naive())
+    }
+
+    // Parsed `conf_start_date` as a UTC calendar date.
+    pub(crate) fn start_date(&self) -> Option<NaiveDate> {
+        Self::parse_date_field(&self.conf_start_date)
+    }
+
+    // Sort key placing parsed deadlines in ascending order before unparseable ones.
+    fn deadline_key(&self) -> (bool, Option<DateTime<Utc>>) {
+        let deadline = self.cfp_deadline_datetime().ok();
+        (deadline.is_none(), deadline)
+    }
+
+    // Sort key placing parsed start dates in ascending order before unparseable ones.
+    fn start_date_key(&self) -> (bool, Option<NaiveDate>) {
+        let start = self.start_date();
+        (start.is_none(), start)
+    }
+}
+
+/// Formats as `{name} ({city}, {country}) - CFP deadline {cfp_deadline}`, e.g.
+/// `RustConf (Portland, USA) - CFP deadline 2030-04-01`.
+///
+/// Empty city or country parts are left out, along with the parentheses when
+/// both are empty. Values are trimmed but otherwise printed as returned by the
+/// API. Use `{:?}` for every field, or [`Conf::summary`] for the id and the
+/// days left.
+impl fmt::Display for Conf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name.trim())?;
+        let location = self.city_country();
+        if !location.is_empty() {
+            write!(f, " ({})", location)?;
+        }
+
+        write!(f, " - CFP deadline {}", self.cfp_deadline.trim())
+    }
+}
+
+impl PartialEq for Conf {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Conf {}
+
+impl Hash for Conf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Ord for Conf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl PartialOrd for Conf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Sort conferences by ascending `cfp_deadline`, with unparseable deadlines
+/// pushed to the end.
+///
+/// Each deadline is parsed once, and conferences with equal deadlines keep
+/// their original relative order.
+pub fn sort_by_deadline(confs: &mut [Conf]) {
+    confs.sort_by_cached_key(Conf::deadline_key);
+}
+
+/// Sort conferences by ascending `conf_start_date`, with unparseable start
+/// dates pushed to the end.
+///
+/// Each start date is parsed once, and conferences starting on the same day
+/// keep their original relative order.
+pub fn sort_by_start_date(confs: &mut [Conf]) {
+    confs.sort_by_cached_key(Conf::start_date_key);
+}
+
+/// Group conferences by `country`.
+///
+/// Keys are normalized to lowercase with surrounding and repeated whitespace
+/// removed, so "Germany" and " germany" land in the same bucket.
+pub fn group_by_country(confs: &[Conf]) -> HashMap<String, Vec<Conf>> {
+    let mut groups: HashMap<String, Vec<Conf>> = HashMap::new();
+    for conf in confs {
+        groups
+            .entry(normalize(&conf.country))
+            .or_default()
+            .push(conf.clone());
+    }
+
+    groups
+}
+
+/// Remove conferences equal to one seen earlier in the list, i.e. with the
+/// same `id`, keeping the first occurrence and preserving the order of first
+/// appearance.
+pub fn dedup_by_id(confs: Vec<Conf>) -> Vec<Conf> {
+    let mut seen = HashSet::new();
+
+    confs.into_iter().filter(|conf| seen.insert(conf.id)).collect()
+}
+
+/// Export conferences as JSON Lines, one compact JSON object per line.
+///
+/// Every line, including the last, ends with `\n`, so the output has no blank
+/// lines and can be appended to an existing file. An empty slice gives an
+/// empty string. Each line can be read back with [`Conf::from_json`].
+///
+/// ```
+/// use cfptime::{conf, Conf};
+///
+/// let jsonl = conf::to_jsonl(&[Conf::new(1, "A"), Conf::new(2, "B")]).unwrap();
+/// let ids: Vec<i32> = jsonl.lines().map(|line| Conf::from_json(line).unwrap().id).collect();
+///
+/// assert_eq!(ids, [1, 2]);
+/// assert!(jsonl.ends_with("}\n"));
+/// ```
+pub fn to_jsonl(confs: &[Conf]) -> Result<String, serde_json::Error> {
+    let mut jsonl = String::new();
+    for conf in confs {
+        jsonl.push_str(&serde_json::to_string(conf)?);
+        jsonl.push('\n');
+    }
+
+    Ok(jsonl)
+}
+
+/// Submission status of a CFP, see [`Conf::cfp_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CfpStatus {
+    /// Accepting submissions with plenty of time left.
+    Open {
+        /// Whole days until the deadline.
+        days_left: i64,
+    },
+    /// Accepting submissions, but the deadline is near.
+    ClosingSoon {
+        /// Whole days until the deadline, `0` when it is today.
+        days_left: i64,
+    },
+    /// The deadline has passed.
+    Closed,
+    /// The deadline is missing or can't be parsed.
+    Unknown,
+}
+
+/// Changes between two fetches of the same list, see [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfDiff {
+    /// Ids only present in the new list, in its order.
+    pub added: Vec<i32>,
+    /// Ids only present in the old list, in its order.
+    pub removed: Vec<i32>,
+    /// New versions of conferences present in both lists with at least one
+    /// changed field, in the order of the new list.
+    pub modified: Vec<Conf>,
+}
+
+impl ConfDiff {
+    /// Whether nothing was added, removed or modified.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Compare two fetches of a list, e.g. successive polls of `get_cfps`.
+///
+/// Conferences are matched by `id`. A conference counts as modified when any
+/// of its fields differ, whereas `==` on [`Conf`] only compares ids.
+pub fn diff(old: &[Conf], new: &[Conf]) -> ConfDiff {
+    let old_by_id: HashMap<i32, &Conf> = old.iter().map(|conf| (conf.id, conf)).collect();
+    let new_ids: HashSet<i32> = new.iter().map(|conf| conf.id).collect();
+
+    let mut changes = ConfDiff::default();
+    for conf in new {
+        match old_by_id.get(&conf.id) {
+            None => changes.added.push(conf.id),
+            Some(previous) if !same_fields(previous, conf) => changes.modified.push(conf.clone()),
+            Some(_) => (),
+        }
+    }
+    changes.removed = old
+        .iter()
+        .map(|conf| conf.id)
+        .filter(|id| !new_ids.contains(id))
+        .collect();
+
+    changes
+}
+
+// Field by field comparison, as opposed to the id-only `PartialEq`.
+fn same_fields(a: &Conf, b: &Conf) -> bool {
+    fn fields(conf: &Conf) -> impl PartialEq + '_ {
+        let Conf {
+            id,
+            name,
+            cfp_deadline,
+            conf_start_date,
+            city,
+            province,
+            country,
+            twitter,
+            website,
+            cfp_details,
+            speaker_benefits,
+            code_of_conduct,
+            created_at,
+            number_of_days,
+        } = conf;
+        (
+            (id, name, cfp_deadline, conf_start_date, city, province, country),
+            (twitter, website, cfp_details, speaker_benefits, code_of_conduct, created_at, number_of_days),
+        )
+    }
+
+    fields(a) == fields(b)
+}
+
+// Normalize free-text values for comparison: lowercase, trimmed and with
+// whitespace runs collapsed to a single space.
+pub(crate) fn normalize(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// Normalize a place name for comparison, like `normalize`.
+#[cfg(not(feature = "unaccent"))]
+pub(crate) fn normalize_place(value: &str) -> String {
+    normalize(value)
+}
+
+// Normalize a place name for comparison, like `normalize` but with diacritics
+// stripped as well, so "Zürich" and "zurich" compare equal.
+#[cfg(feature = "unaccent")]
+pub(crate) fn normalize_place(value: &str) -> String {
+    use unicode_normalization::char::is_combining_mark;
+    use unicode_normalization::UnicodeNormalization;
+
+    normalize(value)
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .nfc()
+        .collect()
+}
+
+// Deserialize an id given either as a JSON number or as a numeric string.
+fn deserialize_id<'de, D>(deserializer: D) -> Result<i32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct IdVisitor;
+
+    impl<'de> Visitor<'de> for IdVisitor {
+        type Value = i32;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an integer id or a string containing one")
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<i32, E> {
+            i32::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<i32, E> {
+            i32::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<i32, E> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+
+    deserializer.deserialize_any(IdVisitor)
+}
+
+// Whether a free-text value is empty or only a placeholder such as "N/A".
+fn is_placeholder(value: &str) -> bool {
+    let value = normalize(value);
+    let value = value.trim_end_matches('.');
+
+    value.is_empty() || PLACEHOLDERS.contains(&value)
+}
+
+// Parse a timestamp or date-only value returned by CFPTime, see
+// `Conf::parse_date_field` for the supported formats. Dates are taken to be
+// midnight UTC.
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    let value = value.trim();
+    let mut error = match DateTime::parse_from_rfc3339(value) {
+        Ok(datetime) => return Ok(datetime.with_timezone(&Utc)),
+        Err(err) => err,
+    };
+    for format in DATETIME_FORMATS {
+        match NaiveDateTime::parse_from_str(value, format) {
+            Ok(datetime) => return Ok(Utc.from_utc_datetime(&datetime)),
+            Err(err) => error = err,
+        }
+    }
+    for format in DATE_FORMATS {
+        match NaiveDate::parse_from_str(value, format) {
+            Ok(date) => return Ok(Utc.from_utc_datetime(&date.and_time(NaiveTime::default()))),
+            Err(err) => error = err,
+        }
+    }
+
+    Err(error)
+}
@@ -0,0 +1,30 @@
+use std::io;
+
+use super::Conf;
+
+/// Export conferences as CSV, with a header row followed by one row per
+/// conference containing every field.
+///
+/// Fields containing commas, quotes or line breaks (commonly `cfp_details`)
+/// are quoted. Requires the `csv` feature.
+///
+/// ```
+/// use cfptime::{conf, Conf};
+///
+/// let mut conf = Conf::new(5, "RustConf");
+/// conf.cfp_details = "a, \"b\"\nc".to_string();
+///
+/// let csv = conf::to_csv(&[conf]).unwrap();
+/// assert!(csv.ends_with("\n5,RustConf,,,,,,,,\"a, \"\"b\"\"\nc\",,,,0\n"));
+/// ```
+pub fn to_csv(confs: &[Conf]) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for conf in confs {
+        writer.serialize(conf)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| csv::Error::from(err.into_error()))?;
+    String::from_utf8(bytes).map_err(|err| csv::Error::from(io::Error::new(io::ErrorKind::InvalidData, err)))
+}
@@ -0,0 +1,63 @@
+use serde_json::{json, Value};
+
+use super::Conf;
+
+/// Export conferences as a GeoJSON `FeatureCollection`, e.g. for a map of
+/// events.
+///
+/// `Conf` has no coordinates, so `geocode` supplies them as `(latitude,
+/// longitude)` in degrees, e.g. from a lookup table keyed by city and country.
+/// Conferences it returns `None` or non-finite coordinates for are skipped.
+/// Each remaining conference becomes a `Point` feature with its `id`, `name`,
+/// `city`, `province`, `country`, `cfp_deadline`, `conf_start_date` and
+/// `website` as properties. Requires the `geojson` feature.
+///
+/// ```
+/// use cfptime::{conf, Conf};
+///
+/// let mut conf = Conf::new(5, "RustConf");
+/// conf.city = "Portland".to_string();
+/// conf.country = "USA".to_string();
+///
+/// let geojson = conf::to_geojson(&[conf, Conf::new(6, "Nowhere")], |conf| {
+///     (conf.city == "Portland").then_some((45.52, -122.68))
+/// })
+/// .unwrap();
+///
+/// let value: serde_json::Value = serde_json::from_str(&geojson).unwrap();
+/// assert_eq!(value["features"].as_array().unwrap().len(), 1);
+/// assert_eq!(value["features"][0]["geometry"]["coordinates"], serde_json::json!([-122.68, 45.52]));
+/// assert_eq!(value["features"][0]["properties"]["city"], "Portland");
+/// ```
+pub fn to_geojson<F>(confs: &[Conf], geocode: F) -> Result<String, serde_json::Error>
+where
+    F: Fn(&Conf) -> Option<(f64, f64)>,
+{
+    let features: Vec<Value> = confs
+        .iter()
+        .filter_map(|conf| {
+            let (latitude, longitude) = geocode(conf)?;
+            if !latitude.is_finite() || !longitude.is_finite() {
+                return None;
+            }
+
+            Some(json!({
+                "type": "Feature",
+                // GeoJSON positions are longitude first.
+                "geometry": {"type": "Point", "coordinates": [longitude, latitude]},
+                "properties": {
+                    "id": conf.id,
+                    "name": conf.name,
+                    "city": conf.city,
+                    "province": conf.province,
+                    "country": conf.country,
+                    "cfp_deadline": conf.cfp_deadline,
+                    "conf_start_date": conf.conf_start_date,
+                    "website": conf.website,
+                },
+            }))
+        })
+        .collect();
+
+    serde_json::to_string(&json!({"type": "FeatureCollection", "features": features}))
+}
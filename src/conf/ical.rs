@@ -0,0 +1,108 @@
+use chrono::Utc;
+
+use super::Conf;
+
+// Maximum length of a content line in octets, excluding the line break.
+const MAX_LINE_LEN: usize = 75;
+
+/// Export CFP deadlines as an iCalendar (`.ics`) document.
+///
+/// Each conference becomes an all-day VEVENT on its `cfp_deadline`, with the
+/// name, website and city/country in the description. Conferences whose
+/// deadline can't be parsed are skipped. Requires the `ical` feature.
+///
+/// Text is escaped and lines are folded at 75 octets, without splitting a
+/// multi-byte character:
+///
+/// ```
+/// use cfptime::{conf, Conf};
+///
+/// let mut rustconf = Conf::new(5, "Rust; Conf, Portland");
+/// rustconf.cfp_deadline = "2030-04-01".to_string();
+/// rustconf.website = "https://rustconf.com".to_string();
+/// let mut long = Conf::new(6, "é".repeat(40));
+/// long.cfp_deadline = "2030-05-01".to_string();
+/// let undated = Conf::new(7, "Undated");
+///
+/// let ics = conf::to_ical(&[rustconf, long, undated]);
+/// assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+/// assert!(!ics.contains("Undated"));
+/// assert!(ics.contains("SUMMARY:CFP deadline: Rust\\; Conf\\, Portland\r\n"));
+/// assert!(ics.contains("DESCRIPTION:Rust\\; Conf\\, Portland\\nhttps://rustconf.com\r\n"));
+/// assert!(!ics.contains(&"é".repeat(40)));
+/// assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+/// assert!(ics.replace("\r\n ", "").contains(&format!("SUMMARY:CFP deadline: {}\r\n", "é".repeat(40))));
+/// ```
+pub fn to_ical(confs: &[Conf]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//cfptime-rs//CFP deadlines//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for conf in confs {
+        let Ok(deadline) = conf.cfp_deadline_datetime() else {
+            continue;
+        };
+        let location = conf.city_country();
+        let description = [conf.name.trim(), conf.website.trim(), location.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:cfp-{}@cfptime.org", conf.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", deadline.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape(&format!("CFP deadline: {}", conf.name.trim()))));
+        lines.push(format!("DESCRIPTION:{}", escape(&description)));
+        if !location.is_empty() {
+            lines.push(format!("LOCATION:{}", escape(&location)));
+        }
+        if let Some(url) = conf.website_url() {
+            lines.push(format!("URL:{}", url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// Escape a TEXT property value.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => (),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+// Fold a content line into CRLF-terminated chunks of at most 75 octets,
+// continuation lines starting with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+
+    folded
+}
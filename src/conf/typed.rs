@@ -0,0 +1,127 @@
+use std::error;
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{parse_datetime, Conf, ConfProblem};
+
+/// A [`Conf`] with its date fields parsed, for consumers who want typed data
+/// without parsing strings on every access.
+///
+/// Built with `ConfTyped::try_from(conf)`, which parses the dates like
+/// [`Conf::cfp_deadline_datetime`] and [`Conf::parse_date_field`]. Serializes
+/// the dates as RFC 3339 timestamps and ISO dates. Requires the `typed`
+/// feature.
+///
+/// ```
+/// use cfptime::conf::ConfTyped;
+/// use cfptime::Conf;
+///
+/// let mut conf = Conf::new(5, "RustConf");
+/// conf.cfp_deadline = "2030-04-01".to_string();
+/// conf.conf_start_date = "September 10, 2030".to_string();
+/// conf.created_at = "2030-01-01T00:00:00Z".to_string();
+///
+/// let typed = ConfTyped::try_from(conf.clone()).unwrap();
+/// assert_eq!(typed.conf_start_date.to_string(), "2030-09-10");
+///
+/// conf.cfp_deadline = "TBA".to_string();
+/// conf.created_at.clear();
+/// let err = ConfTyped::try_from(conf).unwrap_err();
+/// assert_eq!(err.id, 5);
+/// assert_eq!(err.problems.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ConfTyped {
+    pub id: i32,
+    pub name: String,
+    pub cfp_deadline: DateTime<Utc>,
+    pub conf_start_date: NaiveDate,
+    pub city: String,
+    pub province: String,
+    pub country: String,
+    pub twitter: String,
+    pub website: String,
+    pub cfp_details: String,
+    pub speaker_benefits: String,
+    pub code_of_conduct: String,
+    pub created_at: DateTime<Utc>,
+    pub number_of_days: i32,
+}
+
+/// The date fields of a [`Conf`] that couldn't be parsed when converting it
+/// to a [`ConfTyped`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfTypedError {
+    /// `id` of the record.
+    pub id: i32,
+    /// One [`ConfProblem::InvalidDate`] per field that failed, in field order.
+    pub problems: Vec<ConfProblem>,
+}
+
+impl fmt::Display for ConfTypedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "conference {}: ", self.id)?;
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for ConfTypedError {}
+
+impl TryFrom<Conf> for ConfTyped {
+    type Error = ConfTypedError;
+
+    fn try_from(conf: Conf) -> Result<Self, Self::Error> {
+        let mut problems = Vec::new();
+        let mut invalid = |field: &'static str, value: &str| {
+            problems.push(ConfProblem::InvalidDate {
+                field,
+                value: value.to_string(),
+            })
+        };
+
+        let cfp_deadline = parse_datetime(&conf.cfp_deadline).ok();
+        if cfp_deadline.is_none() {
+            invalid("cfp_deadline", &conf.cfp_deadline);
+        }
+        let conf_start_date = Conf::parse_date_field(&conf.conf_start_date);
+        if conf_start_date.is_none() {
+            invalid("conf_start_date", &conf.conf_start_date);
+        }
+        let created_at = parse_datetime(&conf.created_at).ok();
+        if created_at.is_none() {
+            invalid("created_at", &conf.created_at);
+        }
+
+        let (Some(cfp_deadline), Some(conf_start_date), Some(created_at)) = (cfp_deadline, conf_start_date, created_at)
+        else {
+            return Err(ConfTypedError { id: conf.id, problems });
+        };
+
+        Ok(ConfTyped {
+            id: conf.id,
+            name: conf.name,
+            cfp_deadline,
+            conf_start_date,
+            city: conf.city,
+            province: conf.province,
+            country: conf.country,
+            twitter: conf.twitter,
+            website: conf.website,
+            cfp_details: conf.cfp_details,
+            speaker_benefits: conf.speaker_benefits,
+            code_of_conduct: conf.code_of_conduct,
+            created_at,
+            number_of_days: conf.number_of_days,
+        })
+    }
+}
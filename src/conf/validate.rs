@@ -0,0 +1,117 @@
+use std::fmt;
+
+use super::{parse_datetime, Conf};
+
+/// Problems found in one conference record, see [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfValidation {
+    /// `id` of the record.
+    pub id: i32,
+    /// Everything wrong with it, in field order.
+    pub problems: Vec<ConfProblem>,
+}
+
+/// A data-quality problem in a conference record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfProblem {
+    /// A required field (`name`, `cfp_deadline`, `conf_start_date` or
+    /// `country`) is empty.
+    MissingField(&'static str),
+    /// A date field isn't in a supported format, see [`Conf::parse_date_field`].
+    InvalidDate {
+        field: &'static str,
+        value: String,
+    },
+    /// `website` isn't empty but isn't a valid URL either, see [`Conf::website_url`].
+    InvalidWebsite(String),
+    /// `twitter` isn't empty but has no usable handle, see [`Conf::twitter_handle`].
+    InvalidTwitter(String),
+    /// `number_of_days` isn't positive.
+    InvalidNumberOfDays(i32),
+}
+
+impl fmt::Display for ConfProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "{} is empty", field),
+            Self::InvalidDate { field, value } => write!(f, "{} isn't a valid date: {:?}", field, value),
+            Self::InvalidWebsite(website) => write!(f, "website isn't a valid URL: {:?}", website),
+            Self::InvalidTwitter(twitter) => write!(f, "twitter isn't a valid handle: {:?}", twitter),
+            Self::InvalidNumberOfDays(days) => write!(f, "number_of_days isn't positive: {}", days),
+        }
+    }
+}
+
+/// Check every record for data-quality problems, e.g. to report bad data to
+/// the API maintainers.
+///
+/// Flags empty required fields, dates that can't be parsed (`created_at` only
+/// when present), a `website` or `twitter` value that can't be normalized,
+/// and a non-positive `number_of_days`. Only records with at least one
+/// problem are returned, in input order.
+///
+/// ```
+/// use cfptime::{conf, Conf};
+///
+/// let mut conf = Conf::new(5, "RustConf");
+/// conf.cfp_deadline = "2030-04-01".to_string();
+/// conf.conf_start_date = "soon".to_string();
+/// conf.country = "USA".to_string();
+/// conf.number_of_days = 2;
+///
+/// let report = conf::validate(&[conf]);
+/// assert_eq!(report[0].id, 5);
+/// assert_eq!(report[0].problems[0].to_string(), r#"conf_start_date isn't a valid date: "soon""#);
+/// ```
+pub fn validate(confs: &[Conf]) -> Vec<ConfValidation> {
+    confs
+        .iter()
+        .filter_map(|conf| {
+            let problems = problems(conf);
+            if problems.is_empty() {
+                return None;
+            }
+
+            Some(ConfValidation { id: conf.id, problems })
+        })
+        .collect()
+}
+
+// Problems of a single record.
+fn problems(conf: &Conf) -> Vec<ConfProblem> {
+    let mut problems = Vec::new();
+    if conf.name.trim().is_empty() {
+        problems.push(ConfProblem::MissingField("name"));
+    }
+    for (field, value) in [("cfp_deadline", &conf.cfp_deadline), ("conf_start_date", &conf.conf_start_date)] {
+        if value.trim().is_empty() {
+            problems.push(ConfProblem::MissingField(field));
+        } else if parse_datetime(value).is_err() {
+            problems.push(ConfProblem::InvalidDate {
+                field,
+                value: value.clone(),
+            });
+        }
+    }
+    if conf.country.trim().is_empty() {
+        problems.push(ConfProblem::MissingField("country"));
+    }
+    if !conf.twitter.trim().is_empty() && conf.twitter_handle().is_none() {
+        problems.push(ConfProblem::InvalidTwitter(conf.twitter.clone()));
+    }
+    if !conf.website.trim().is_empty() && conf.website_url().is_none() {
+        problems.push(ConfProblem::InvalidWebsite(conf.website.clone()));
+    }
+    if !conf.created_at.trim().is_empty() && parse_datetime(&conf.created_at).is_err() {
+        problems.push(ConfProblem::InvalidDate {
+            field: "created_at",
+            value: conf.created_at.clone(),
+        });
+    }
+    if conf.number_of_days <= 0 {
+        problems.push(ConfProblem::InvalidNumberOfDays(conf.number_of_days));
+    }
+
+    problems
+}
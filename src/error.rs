@@ -0,0 +1,247 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{header, StatusCode};
+
+use crate::rate_limit::retry_after;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::retry::DeadlineExceeded;
+use crate::read_body;
+
+/// Errors returned by the CFPTime client.
+///
+/// More variants may be added in future releases, so matches on it need a
+/// wildcard arm.
+///
+/// Errors are cheap to clone, with the underlying errors behind an `Arc`, so
+/// a failed request shared by concurrent callers is returned to each of them.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum CFPError {
+    /// The HTTP client couldn't be built, or the request couldn't be sent or
+    /// its response read, e.g. a DNS failure or a dropped connection.
+    Network(Arc<reqwest::Error>),
+    /// The request didn't complete within the configured timeout.
+    Timeout(Arc<reqwest::Error>),
+    /// A middleware layer such as the retry policy failed.
+    Middleware(Arc<anyhow::Error>),
+    /// A request body couldn't be encoded as JSON, or local JSON such as
+    /// [`CFPTime::from_json_str`](crate::CFPTime::from_json_str) input isn't valid.
+    Decode(Arc<serde_json::Error>),
+    /// A response body isn't the JSON that was expected.
+    DecodeAt {
+        /// The resolved URL of the request.
+        url: String,
+        /// Path to the value that failed, e.g. `[3].number_of_days`. Only set
+        /// with the `path-to-error` feature.
+        path: Option<String>,
+        /// The body around the position of the error.
+        snippet: String,
+        source: Arc<serde_json::Error>,
+    },
+    /// The response declared a non-JSON content type, e.g. an HTML error page
+    /// served during an outage or by a captive portal.
+    NotJson {
+        /// The resolved URL of the request.
+        url: String,
+        content_type: String,
+        /// The start of the response body.
+        snippet: String,
+    },
+    /// The API replied 429 Too Many Requests and retries are exhausted.
+    RateLimited {
+        /// The resolved URL of the request.
+        url: String,
+        /// How long the server asked to wait before retrying.
+        retry_after: Duration,
+    },
+    /// The API replied with an unexpected status code.
+    Api {
+        /// The resolved URL of the request.
+        url: String,
+        status: StatusCode,
+        body: String,
+        /// Response headers, e.g. `Retry-After` or `X-RateLimit-Remaining`.
+        headers: Box<header::HeaderMap>,
+    },
+    /// The endpoint or request path isn't a valid URL.
+    Url(url::ParseError),
+    /// A configured header value, such as the API token, contains characters
+    /// that aren't allowed in HTTP headers.
+    InvalidHeader(Arc<header::InvalidHeaderValue>),
+    /// A local file couldn't be read.
+    Io(Arc<io::Error>),
+    /// The response body is larger than the configured maximum, see
+    /// [`CFPTimeBuilder::max_response_size`](crate::CFPTimeBuilder::max_response_size).
+    ResponseTooLarge {
+        /// The resolved URL of the request.
+        url: String,
+        /// The maximum size in bytes.
+        limit: usize,
+    },
+    /// A pagination cursor points to another scheme, host or port than the
+    /// endpoint. It isn't followed, so the API token isn't sent elsewhere.
+    UntrustedCursor {
+        /// The rejected `next` URL.
+        url: String,
+    },
+    /// A request and its retries didn't complete within
+    /// [`CFPTimeBuilder::max_elapsed_time`](crate::CFPTimeBuilder::max_elapsed_time).
+    DeadlineExceeded {
+        /// The resolved URL of the request.
+        url: String,
+        /// The time budget that ran out.
+        elapsed: Duration,
+    },
+}
+
+impl CFPError {
+    /// The resolved URL of the failed request, when known.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::Network(err) | Self::Timeout(err) => err.url().map(|url| url.as_str()),
+            Self::NotJson { url, .. }
+            | Self::RateLimited { url, .. }
+            | Self::Api { url, .. }
+            | Self::ResponseTooLarge { url, .. }
+            | Self::DecodeAt { url, .. }
+            | Self::UntrustedCursor { url }
+            | Self::DeadlineExceeded { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    // Build an error from an unsuccessful response, consuming its body of at
+    // most `max_size` bytes.
+    pub(crate) async fn from_response(resp: reqwest::Response, max_size: usize) -> Self {
+        let url = resp.url().to_string();
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            if let Some(retry_after) = retry_after(resp.headers()) {
+                return Self::RateLimited { url, retry_after };
+            }
+        }
+
+        let headers = Box::new(resp.headers().clone());
+        match read_body(resp, max_size, |_| ()).await {
+            Ok(body) => Self::Api {
+                url,
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+                headers,
+            },
+            Err(err) => err,
+        }
+    }
+}
+
+impl fmt::Display for CFPError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Network(err) => write!(f, "CFPError: network -> {}", err),
+            Self::Timeout(err) => write!(f, "CFPError: timeout -> {}", err),
+            Self::Middleware(err) => write!(f, "CFPError: middleware -> {}", err),
+            Self::Decode(err) => write!(f, "CFPError: decode -> {}", err),
+            Self::DecodeAt { url, path: Some(path), snippet, source } => write!(
+                f,
+                "CFPError: decode -> {} at {}, url -> {}, body -> {}",
+                source, path, url, snippet
+            ),
+            Self::DecodeAt { url, path: None, snippet, source } => {
+                write!(f, "CFPError: decode -> {}, url -> {}, body -> {}", source, url, snippet)
+            }
+            Self::NotJson { url, content_type, snippet } => write!(
+                f,
+                "CFPError: unexpected content type -> {}, url -> {}, body -> {}",
+                content_type, url, snippet
+            ),
+            Self::RateLimited { url, retry_after } => {
+                write!(f, "CFPError: rate limited -> retry after {:?}, url -> {}", retry_after, url)
+            }
+            Self::Api { url, status, body, .. } => {
+                write!(f, "CFPError: status code -> {}, url -> {}, body -> {}", status, url, body)
+            }
+            Self::Url(err) => write!(f, "CFPError: url -> {}", err),
+            Self::InvalidHeader(err) => write!(f, "CFPError: invalid header -> {}", err),
+            Self::Io(err) => write!(f, "CFPError: io -> {}", err),
+            Self::UntrustedCursor { url } => write!(f, "CFPError: cursor on another origin -> {}", url),
+            Self::ResponseTooLarge { url, limit } => {
+                write!(f, "CFPError: response too large -> more than {} bytes, url -> {}", limit, url)
+            }
+            Self::DeadlineExceeded { url, elapsed } => {
+                write!(f, "CFPError: deadline exceeded -> after {:?}, url -> {}", elapsed, url)
+            }
+        }
+    }
+}
+
+// This is important for other errors to wrap this one.
+impl error::Error for CFPError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Network(err) | Self::Timeout(err) => Some(err.as_ref()),
+            Self::Middleware(err) => Some(err.as_ref().as_ref()),
+            Self::Decode(err) => Some(err.as_ref()),
+            Self::DecodeAt { source, .. } => Some(source.as_ref()),
+            Self::NotJson { .. }
+            | Self::RateLimited { .. }
+            | Self::Api { .. }
+            | Self::ResponseTooLarge { .. }
+            | Self::UntrustedCursor { .. }
+            | Self::DeadlineExceeded { .. } => None,
+            Self::Url(err) => Some(err),
+            Self::InvalidHeader(err) => Some(err.as_ref()),
+            Self::Io(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for CFPError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout(Arc::new(err))
+        } else {
+            Self::Network(Arc::new(err))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<reqwest_middleware::Error> for CFPError {
+    fn from(err: reqwest_middleware::Error) -> Self {
+        match err {
+            reqwest_middleware::Error::Reqwest(err) => err.into(),
+            reqwest_middleware::Error::Middleware(err) => match err.downcast::<DeadlineExceeded>() {
+                Ok(DeadlineExceeded { url, elapsed }) => Self::DeadlineExceeded { url, elapsed },
+                Err(err) => Self::Middleware(Arc::new(err)),
+            },
+        }
+    }
+}
+
+impl From<serde_json::Error> for CFPError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Decode(Arc::new(err))
+    }
+}
+
+impl From<url::ParseError> for CFPError {
+    fn from(err: url::ParseError) -> Self {
+        Self::Url(err)
+    }
+}
+
+impl From<header::InvalidHeaderValue> for CFPError {
+    fn from(err: header::InvalidHeaderValue) -> Self {
+        Self::InvalidHeader(Arc::new(err))
+    }
+}
+
+impl From<io::Error> for CFPError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(Arc::new(err))
+    }
+}
@@ -6,59 +6,187 @@
  * Example:
  *
  * ```
- * use cfptime_api::{CFPTime, Conf};
+ * use cfptime::{CFPTime, Conf};
  *
  * async fn get_all_cfps() {
  *     let cfptime = CFPTime::new();
  *
  *     let confs: Vec<Conf> = cfptime.get_cfps().await.unwrap();
- *     for conf in confs.clone().iter() {
- *         println!(
- *             "#{:?} - Name: {:?}, Country: {:?}, Website: {:?}",
- *             conf.id,
- *             conf.name,
- *             conf.country,
- *             conf.website
- *         );
+ *     for conf in confs.iter() {
+ *         println!("{}", conf.summary());
  *     }
  * }
  * ```
  */
-use std::error;
-use std::fmt;
-use std::fmt::Debug;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
 
-use anyhow::{Result, bail};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+#[cfg(feature = "stream")]
+use futures::{stream, Stream, TryStreamExt};
+use futures::StreamExt;
 use reqwest::{header, Method, Request, Url, StatusCode};
-use serde::{Serialize, Deserialize};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest_retry::policies::ExponentialBackoff;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::Span;
+
+mod cache;
+pub mod client;
+pub mod conf;
+mod error;
+#[cfg(all(feature = "metrics", not(target_arch = "wasm32")))]
+mod metrics;
+pub mod query;
+mod rate_limit;
+#[cfg(not(target_arch = "wasm32"))]
+mod retry;
+#[cfg(not(target_arch = "wasm32"))]
+mod single_flight;
+pub mod transport;
+
+use cache::Cache;
+#[cfg(not(target_arch = "wasm32"))]
+use single_flight::SingleFlight;
+use conf::{dedup_by_id, group_by_country, normalize, normalize_place, sort_by_deadline, sort_by_start_date};
+#[cfg(not(target_arch = "wasm32"))]
+use rate_limit::{RateLimitMiddleware, RateLimitStrategy};
+#[cfg(not(target_arch = "wasm32"))]
+use retry::{Backoff, ElapsedLimit, IdempotentOnly, OnRetry, RetryObserver};
+pub use client::CfpClient;
+pub use conf::{Conf, NewConf};
+pub use error::CFPError;
+pub use query::ConfQuery;
+pub use transport::HttpTransport;
+#[cfg(not(target_arch = "wasm32"))]
+pub use transport::MockTransport;
+
+// Maximum number of requests `get_cfps_by_ids` keeps in flight.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
 
 // Endpoint for the CFPTime API.
 const ENDPOINT: &str = "https://api.cfptime.org/api/";
 
+// Maximum number of characters of a response body kept in errors.
+const SNIPPET_LEN: usize = 512;
+
+// Default total timeout for each request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// User-Agent sent unless the builder sets another one.
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_USER_AGENT: &str = concat!("cfptime-rs/", env!("CARGO_PKG_VERSION"));
+
+// Consecutive failures after which `CFPTime::watch` stops doubling its wait.
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+const MAX_WATCH_BACKOFF_EXP: u32 = 5;
+
+// Shortest wait between two fetches of `CFPTime::watch`.
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+const MIN_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+// Default cap on the size of a response body.
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
+// Default budget for a request including all of its retries.
+const DEFAULT_MAX_ELAPSED_TIME: Duration = Duration::from_secs(120);
+
 // Entrypoint for interacting with the CFPTime API.
+//
+// Cloning is cheap: clones share the same HTTP transport, connection pool and
+// retry/tracing middleware behind an `Arc`, only the endpoint is copied.
+// Concurrent calls to the same list method, across clones, share a single
+// HTTP request, except on wasm32.
+#[derive(Clone)]
 pub struct CFPTime {
-    pub(crate) http_client: reqwest_middleware::ClientWithMiddleware,
+    pub(crate) transport: Arc<dyn HttpTransport>,
+    pub(crate) endpoint: String,
+    pub(crate) cache: Option<Arc<Cache>>,
+    pub(crate) etags: Arc<Mutex<HashMap<String, String>>>,
+    // Pre-built, sensitive `Authorization` header value.
+    pub(crate) auth: Option<header::HeaderValue>,
+    // Extra headers sent with every request, see `default_header`.
+    pub(crate) default_headers: header::HeaderMap,
+    // List requests currently in flight, shared by concurrent callers.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) flights: Arc<SingleFlight>,
+    // Largest response body read before failing, in bytes.
+    pub(crate) max_response_size: usize,
 }
 
 impl CFPTime {
     /// Create a new CFPTime client struct.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying HTTP client can't be built, see [`CFPTime::try_new`].
     pub fn new() -> Self {
-        let http = reqwest::Client::builder().build();
-        match http {
-            Ok(lclient) => {
-                let retry_policy = reqwest_retry::policies::ExponentialBackoff::builder().build_with_max_retries(3);
-                let client = reqwest_middleware::ClientBuilder::new(lclient)
-                .with(reqwest_tracing::TracingMiddleware::default())
-                .with(reqwest_retry::RetryTransientMiddleware::new_with_policy(retry_policy))
-                .build();
-
-                Self {
-                    http_client: client,
-                }
-            }
-            Err(err) => panic!("creating client failed: {err:?}"),
-        }
+        Self::builder().build()
+    }
+
+    /// Create a new CFPTime client struct, returning an error if the underlying
+    /// HTTP client can't be built (e.g. TLS backend or proxy configuration issues).
+    pub fn try_new() -> Result<Self, CFPError> {
+        Self::builder().try_build()
+    }
+
+    /// Create a new CFPTime client struct that talks to `endpoint` instead of
+    /// the public CFPTime API, e.g. a staging server or a local mock.
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self::builder().endpoint(endpoint).build()
+    }
+
+    /// Create a new CFPTime client struct on top of an existing `reqwest::Client`,
+    /// sharing its connection pool, proxy and TLS configuration.
+    ///
+    /// The tracing and retry middleware are still layered on top of `client`.
+    pub fn from_client(client: reqwest::Client) -> Self {
+        Self::builder().client(client).build()
+    }
+
+    /// Create a new CFPTime client struct that sends its requests through
+    /// `transport`, e.g. a [`MockTransport`] in tests.
+    pub fn with_transport(transport: impl HttpTransport + 'static) -> Self {
+        Self::builder().transport(transport).build()
+    }
+
+    /// Create a new CFPTime client struct that caches the responses of
+    /// `get_cfps`, `get_confs` and `get_upcoming` for `ttl`.
+    pub fn with_cache(ttl: Duration) -> Self {
+        Self::builder().cache(ttl).build()
+    }
+
+    /// Create a new CFPTime client struct that authenticates every request
+    /// with `Authorization: Bearer {token}`, as required by write endpoints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token` isn't a valid header value, see [`CFPTimeBuilder::token`].
+    pub fn with_token(token: String) -> Self {
+        Self::builder().token(token).build()
+    }
+
+    /// Parse a saved list response, e.g. the body of `/api/cfps/`, without
+    /// touching the network.
+    pub fn from_json_str(s: &str) -> Result<Vec<Conf>, CFPError> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Read and parse a saved list response from the file at `path`, see
+    /// [`CFPTime::from_json_str`].
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Vec<Conf>, CFPError> {
+        let json = fs::read_to_string(path)?;
+        Self::from_json_str(&json)
+    }
+
+    /// Create a builder for configuring the CFPTime client.
+    pub fn builder() -> CFPTimeBuilder {
+        CFPTimeBuilder::default()
     }
 
     pub(crate) fn request<B>(
@@ -66,192 +194,1687 @@ impl CFPTime {
         method: Method,
         path: String,
         body: B,
-    ) -> Result<Request>
+    ) -> Result<Request, CFPError>
     where
         B: Serialize,
     {
-        let base = Url::parse(ENDPOINT)?;
-        let url = base.join(&path)?;
+        let url = join_url(&self.endpoint, &path)?;
 
+        self.request_url(method, url, body)
+    }
+
+    // Build a request for an absolute `url`, e.g. a pagination cursor.
+    pub(crate) fn request_url<B>(
+        &self,
+        method: Method,
+        url: Url,
+        body: B,
+    ) -> Result<Request, CFPError>
+    where
+        B: Serialize,
+    {
         // Set the default headers.
-        let mut headers = header::HeaderMap::new();
-        headers.append(
-            header::CONTENT_TYPE,
-            header::HeaderValue::from_static("application/json; charset=utf-8"),
-        );
-        let mut rb = self.http_client.request(method.clone(), url).headers(headers);
-
-        // Add the body, this is to ensure our GET and DELETE calls succeed.
-        if method != Method::GET && method != Method::DELETE {
-            rb = rb.json(&body);
+        let mut headers = self.default_headers.clone();
+        if !headers.contains_key(header::CONTENT_TYPE) {
+            headers.insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("application/json; charset=utf-8"),
+            );
+        }
+        if let Some(auth) = &self.auth {
+            headers.insert(header::AUTHORIZATION, auth.clone());
+        }
+        let mut request = Request::new(method.clone(), url);
+        *request.headers_mut() = headers;
+
+        // Add the body, this is to ensure our GET, HEAD and DELETE calls succeed.
+        if method != Method::GET && method != Method::HEAD && method != Method::DELETE {
+            *request.body_mut() = Some(serde_json::to_vec(&body)?.into());
+        }
+
+        Ok(request)
+    }
+
+    // Build a request for a `next` cursor, which must be on the endpoint's
+    // origin since the request carries the API token.
+    fn cursor_request(&self, next: &str) -> Result<Request, CFPError> {
+        let url = Url::parse(next)?;
+        if url.origin() != Url::parse(&self.endpoint)?.origin() {
+            return Err(CFPError::UntrustedCursor { url: next.to_string() });
         }
 
-        // Build the request.
-        Ok(rb.build()?)
+        self.request_url(Method::GET, url, ())
     }
 
+    #[tracing::instrument(skip_all, fields(path = "cfps", status, count))]
     pub async fn get_cfps(
         &self,
-    ) -> Result<Vec<Conf>> {
+    ) -> Result<Vec<Conf>, CFPError> {
+        self.get_list("cfps").await
+    }
+
+    /// Fetch the first page of CFPs along with the pagination cursors.
+    ///
+    /// An API returning a bare array instead of a DRF-style envelope yields a
+    /// single page holding every CFP, with no `next` or `previous` cursor.
+    ///
+    /// A 200 response with an empty body, which the API has been seen to send
+    /// while it is being deployed, is taken as an empty list here and in the
+    /// other list methods such as [`CFPTime::get_cfps`], rather than failing.
+    /// Single-item methods such as [`CFPTime::get_cfp`] return
+    /// [`CFPError::DecodeAt`] for an empty body instead.
+    #[tracing::instrument(skip_all, fields(path = "cfps", status, count))]
+    pub async fn get_cfps_paginated(
+        &self,
+    ) -> Result<Paginated<Conf>, CFPError> {
         let request = self.request(
             Method::GET,
             "cfps".to_string(),
             (),
         )?;
 
-        let resp = self.http_client.execute(request).await?;
+        self.fetch_page(request).await
+    }
+
+    /// Fetch every page of CFPs, following the `next` cursors, see
+    /// [`CFPTime::get_cfps_paginated`].
+    ///
+    /// [`CFPTime::get_cfps`] and the other list methods follow the cursors
+    /// the same way, so they never return a partial list.
+    ///
+    /// Stops when a page has no `next` cursor or one leading back to a page
+    /// already fetched, so a misbehaving server can't cause an endless loop or
+    /// duplicate results. A
+    /// cursor on another scheme, host or port than the endpoint fails with
+    /// [`CFPError::UntrustedCursor`] instead of receiving the API token.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_all_cfps_paginated(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let request = self.request(
+            Method::GET,
+            "cfps".to_string(),
+            (),
+        )?;
+        let url = request.url().to_string();
+        let page = self.fetch_page(request).await?;
+
+        self.follow_pages(&url, page).await
+    }
+
+    // Collect the items of `page`, fetched from `url`, and of every page after
+    // it, following the `next` cursors until there are none or one leads back
+    // to a page already fetched.
+    async fn follow_pages(
+        &self,
+        url: &str,
+        mut page: Paginated<Conf>,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = std::mem::take(&mut page.results);
+        let mut seen = HashSet::from([page_key(url)]);
+        while let Some(next) = page.next.take() {
+            if !seen.insert(page_key(&next)) {
+                break;
+            }
+
+            let request = self.cursor_request(&next)?;
+            page = self.fetch_page(request).await?;
+            confs.append(&mut page.results);
+        }
+
+        Ok(confs)
+    }
+
+    /// Start a [`ConfQuery`] over the CFP list, e.g.
+    /// `client.query().country("France").open_only().limit(10).fetch().await`.
+    pub fn query(&self) -> ConfQuery<'_> {
+        ConfQuery::new(self)
+    }
+
+    /// Fetch all CFPs as undecoded JSON, e.g. to read fields the API added
+    /// that [`Conf`] doesn't model yet.
+    ///
+    /// Returns a JSON array of every CFP: the `next` cursors of a DRF-style
+    /// envelope are followed like in [`CFPTime::get_cfps`] and the `results`
+    /// of each page concatenated. An empty body is an empty array. The
+    /// response isn't cached, even when the client has a cache.
+    #[tracing::instrument(skip_all, fields(path = "cfps", status))]
+    pub async fn get_cfps_raw(
+        &self,
+    ) -> Result<serde_json::Value, CFPError> {
+        let request = self.request(
+            Method::GET,
+            "cfps".to_string(),
+            (),
+        )?;
+        let mut seen = HashSet::from([page_key(request.url().as_str())]);
+        let mut page = self.fetch_raw_page(request).await?;
+
+        let mut cfps = Vec::new();
+        loop {
+            let next = match &mut page {
+                serde_json::Value::Array(items) => {
+                    cfps.append(items);
+                    None
+                }
+                serde_json::Value::Object(envelope) => match envelope.get_mut("results") {
+                    Some(serde_json::Value::Array(results)) => {
+                        cfps.append(results);
+                        envelope.get("next").and_then(|next| next.as_str()).map(str::to_string)
+                    }
+                    // Not a list, e.g. a new response format, returned as is.
+                    _ => return Ok(page),
+                },
+                _ => return Ok(page),
+            };
+
+            match next {
+                Some(next) if seen.insert(page_key(&next)) => {
+                    let request = self.cursor_request(&next)?;
+                    page = self.fetch_raw_page(request).await?;
+                }
+                _ => return Ok(serde_json::Value::Array(cfps)),
+            }
+        }
+    }
+
+    // Send a request and decode its response as undecoded JSON, with an empty
+    // body taken as an empty list like in `decode_list_body`.
+    async fn fetch_raw_page(
+        &self,
+        request: Request,
+    ) -> Result<serde_json::Value, CFPError> {
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
         match resp.status() {
             StatusCode::OK => (),
-            s => {
-                bail!("status code: {}, body: {:?}", s, resp.text().await?);
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
             }
         };
 
-        let confs: Vec<Conf> = resp.json().await?;
+        let content_type = content_type(&resp);
+        let url = resp.url().to_string();
+        let body = read_body(resp, self.max_response_size, |_| ()).await?;
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(serde_json::Value::Array(Vec::new()));
+        }
+
+        decode_body(content_type, url, &body)
+    }
+
+    /// Fetch all CFPs and keep those for which `pred` returns `true`, e.g.
+    /// `client.get_cfps_filtered(|c| c.is_open() && c.country == "Germany")`.
+    ///
+    /// Filtering happens client-side, so every call still downloads the full list.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_cfps_filtered<F>(
+        &self,
+        pred: F,
+    ) -> Result<Vec<Conf>, CFPError>
+    where
+        F: Fn(&Conf) -> bool,
+    {
+        let confs = self.get_cfps().await?;
+
+        Ok(confs.into_iter().filter(|conf| pred(conf)).collect())
+    }
+
+    /// Fetch all CFPs and keep those whose `country` matches `country`,
+    /// ignoring case and surrounding whitespace.
+    ///
+    /// Filtering happens client-side and matches names literally, so aliases
+    /// such as "USA" and "United States" are not treated as equivalent.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_cfps_by_country(
+        &self,
+        country: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let country = normalize(country);
+
+        self.get_cfps_filtered(|conf| normalize(&conf.country) == country)
+            .await
+    }
+
+    /// Fetch all CFPs sorted by ascending `cfp_deadline`, see [`conf::sort_by_deadline`].
+    #[tracing::instrument(skip_all)]
+    pub async fn get_cfps_sorted_by_deadline(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = self.get_cfps().await?;
+        sort_by_deadline(&mut confs);
+
+        Ok(confs)
+    }
+
+    /// Fetch the CFPs still open for submission, sorted by nearest deadline.
+    ///
+    /// Unlike [`CFPTime::get_upcoming`], which lists conferences that start
+    /// soon, this keeps CFPs whose submission deadline hasn't passed yet, see
+    /// [`Conf::is_open`].
+    #[tracing::instrument(skip_all)]
+    pub async fn get_open_cfps(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = self.get_cfps_filtered(Conf::is_open).await?;
+        sort_by_deadline(&mut confs);
 
         Ok(confs)
     }
 
+    /// Fetch the `n` open CFPs closing soonest, nearest deadline first.
+    ///
+    /// Returns fewer than `n` CFPs when not enough are open, see
+    /// [`CFPTime::get_open_cfps`].
+    #[tracing::instrument(skip_all)]
+    pub async fn get_next_closing(
+        &self,
+        n: usize,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = self.get_open_cfps().await?;
+        confs.truncate(n);
+
+        Ok(confs)
+    }
+
+    /// Fetch one page of CFPs, where `page` is zero-based and holds up to
+    /// `per_page` items.
+    ///
+    /// The API has no pagination parameters, so the full list is fetched and
+    /// sliced client-side. A `per_page` of zero yields an empty page with
+    /// `has_more` false, so loops until `has_more` is false terminate.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_cfps_paged(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<Page<Conf>, CFPError> {
+        let mut confs = self.get_cfps().await?;
+        let total = confs.len();
+        let start = page.saturating_mul(per_page).min(total);
+        let end = start.saturating_add(per_page).min(total);
+
+        Ok(Page {
+            items: confs.drain(start..end).collect(),
+            page,
+            per_page,
+            total,
+            has_more: per_page > 0 && end < total,
+        })
+    }
+
+    #[tracing::instrument(skip_all, fields(path = "cfps/{id}", status))]
     pub async fn get_cfp(
         &self,
         cfp_id: i32,
-    ) -> Result<Conf> {
+    ) -> Result<Conf, CFPError> {
         let request = self.request(
             Method::GET,
-            format!("{}/{}/", "cfps".to_string(), cfp_id.to_string()),
+            format!("cfps/{}/", cfp_id),
             (),
         )?;
 
-        let resp = self.http_client.execute(request).await?;
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
         match resp.status() {
             StatusCode::OK => (),
-            s => {
-                bail!("status code: {}, body: {:?}", s, resp.text().await?);
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
             }
         };
 
-        let conf: Conf = resp.json().await?;
+        let conf: Conf = decode(resp, self.max_response_size).await?;
 
         Ok(conf)
     }
 
+    /// Find the CFP of the conference `conf_id`, or `None` when it has none.
+    ///
+    /// The conference and the CFP list are fetched concurrently, and the first
+    /// CFP matching one of these rules, tried in order, is returned:
+    ///
+    /// 1. The same `id` as the conference.
+    /// 2. The same `name`, ignoring case and extra whitespace.
+    /// 3. The same website, ignoring the scheme, a leading `www.`, case and a
+    ///    trailing slash (see [`Conf::website_url`]).
+    ///
+    /// Fails like [`CFPTime::get_conf`] when the conference doesn't exist.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_cfp_for_conf(
+        &self,
+        conf_id: i32,
+    ) -> Result<Option<Conf>, CFPError> {
+        let (conf, mut cfps) = futures::future::try_join(self.get_conf(conf_id), self.get_cfps()).await?;
+        let name = normalize(&conf.name);
+        let website = website_key(&conf);
+
+        let position = cfps
+            .iter()
+            .position(|cfp| cfp.id == conf.id)
+            .or_else(|| cfps.iter().position(|cfp| !name.is_empty() && normalize(&cfp.name) == name))
+            .or_else(|| {
+                let website = website.as_ref()?;
+                cfps.iter().position(|cfp| website_key(cfp).as_ref() == Some(website))
+            });
+
+        Ok(position.map(|index| cfps.swap_remove(index)))
+    }
+
+    /// Fetch several CFPs by id concurrently, at most 8 requests at a time.
+    ///
+    /// Results are returned in the same order as `ids`, and one failing id
+    /// doesn't affect the others.
+    #[tracing::instrument(skip_all, fields(path = "cfps/{id}", status, count))]
+    pub async fn get_cfps_by_ids(&self, ids: &[i32]) -> Vec<Result<Conf, CFPError>> {
+        let cfps: Vec<Result<Conf, CFPError>> = futures::stream::iter(ids.iter().map(|&id| self.get_cfp(id)))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect()
+            .await;
+        Span::current().record("count", cfps.iter().filter(|cfp| cfp.is_ok()).count());
+
+        cfps
+    }
+
+    #[tracing::instrument(skip_all, fields(path = "conferences", status, count))]
     pub async fn get_confs(
         &self,
-    ) -> Result<Vec<Conf>> {
+    ) -> Result<Vec<Conf>, CFPError> {
+        self.get_list("conferences").await
+    }
+
+    #[tracing::instrument(skip_all, fields(path = "conferences/{id}", status))]
+    pub async fn get_conf(
+        &self,
+        conf_id: i32,
+    ) -> Result<Conf, CFPError> {
         let request = self.request(
             Method::GET,
-            "conferences".to_string(),
+            format!("conferences/{}/", conf_id),
             (),
         )?;
 
-        let resp = self.http_client.execute(request).await?;
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
         match resp.status() {
             StatusCode::OK => (),
-            s => {
-                bail!("status code: {}, body: {:?}", s, resp.text().await?);
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
             }
         };
 
-        let confs: Vec<Conf> = resp.json().await?;
+        let conf: Conf = decode(resp, self.max_response_size).await?;
 
-        Ok(confs)
+        Ok(conf)
     }
 
-    pub async fn get_conf(
+    /// Submit a new conference, returning it as stored by the server with its
+    /// assigned `id` and `created_at`.
+    ///
+    /// Requires an authenticated client, see [`CFPTime::with_token`]. Not
+    /// retried on transient errors, see [`CFPTimeBuilder::max_retries`].
+    #[tracing::instrument(skip_all, fields(path = "conferences", status))]
+    pub async fn create_conf(
         &self,
-        conf_id: i32,
-    ) -> Result<Conf> {
+        new: NewConf,
+    ) -> Result<Conf, CFPError> {
+        let request = self.request(
+            Method::POST,
+            "conferences/".to_string(),
+            new,
+        )?;
+
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
+        match resp.status() {
+            StatusCode::OK | StatusCode::CREATED => (),
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
+            }
+        };
+
+        let conf: Conf = decode(resp, self.max_response_size).await?;
+
+        Ok(conf)
+    }
+
+    /// Fetch all conferences, calling `on_chunk` with the number of bytes
+    /// received so far each time a chunk of the response body arrives.
+    ///
+    /// Meant for progress bars on slow connections. The body is decoded once
+    /// it is complete, and dropping the future cancels the download. Unlike
+    /// [`CFPTime::get_confs`] the response is never cached. Further pages of
+    /// a paginated response are fetched afterwards without progress reports.
+    #[tracing::instrument(skip_all, fields(path = "conferences", status, count))]
+    pub async fn get_confs_with_progress(
+        &self,
+        on_chunk: impl Fn(usize),
+    ) -> Result<Vec<Conf>, CFPError> {
         let request = self.request(
             Method::GET,
-            format!("{}/{}/", "conferences".to_string(), conf_id.to_string()),
+            "conferences".to_string(),
             (),
         )?;
 
-        let resp = self.http_client.execute(request).await?;
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
         match resp.status() {
             StatusCode::OK => (),
-            s => {
-                bail!("status code: {}, body: {:?}", s, resp.text().await?);
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
             }
         };
 
-        let conf: Conf = resp.json().await?;
+        let content_type = content_type(&resp);
+        let url = resp.url().to_string();
+        let body = read_body(resp, self.max_response_size, on_chunk).await?;
 
-        Ok(conf)
+        let page = decode_list_body(content_type, url.clone(), &body)?;
+        let confs = self.follow_pages(&url, page).await?;
+        Span::current().record("count", confs.len());
+
+        Ok(confs)
     }
 
+    /// Fetch both CFPs and conferences as one list without duplicate ids.
+    ///
+    /// CFPs come first, and when an id appears on both endpoints the CFP
+    /// record is kept, see [`conf::dedup_by_id`].
+    #[tracing::instrument(skip_all)]
+    pub async fn get_all_unique(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = self.get_cfps().await?;
+        confs.extend(self.get_confs().await?);
+
+        Ok(dedup_by_id(confs))
+    }
+
+    /// Fetch both CFPs and conferences and merge the records sharing an `id`.
+    ///
+    /// The CFP record takes precedence: each of its text fields that is empty
+    /// (after trimming) is filled from the conference record, and so is a
+    /// `number_of_days` that isn't positive. CFPs come first in the result,
+    /// followed by conferences without a CFP, each in API order. Both lists
+    /// are fetched concurrently.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_enriched(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let (cfps, confs) = futures::future::try_join(self.get_cfps(), self.get_confs()).await?;
+        let mut confs_by_id: HashMap<i32, Conf> = HashMap::new();
+        for conf in confs.iter().rev() {
+            confs_by_id.insert(conf.id, conf.clone());
+        }
+
+        let mut enriched: Vec<Conf> = cfps
+            .into_iter()
+            .map(|mut cfp| {
+                if let Some(conf) = confs_by_id.get(&cfp.id) {
+                    cfp.fill_from(conf);
+                }
+                cfp
+            })
+            .collect();
+        enriched.extend(confs);
+
+        Ok(dedup_by_id(enriched))
+    }
+
+    /// Fetch the conferences added within the last `since`, based on `created_at`.
+    ///
+    /// Conferences whose `created_at` can't be parsed are skipped.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_recently_added(
+        &self,
+        since: Duration,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let cutoff = chrono::Duration::from_std(since)
+            .ok()
+            .and_then(|since| Utc::now().checked_sub_signed(since));
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| match conf.created_at_datetime() {
+                Ok(created_at) => cutoff.is_none_or(|cutoff| created_at >= cutoff),
+                Err(_) => false,
+            })
+            .collect())
+    }
+
+    /// Fetch the conferences created at or after `since`, e.g. for an
+    /// incremental sync job that remembers when it last ran.
+    ///
+    /// The API has no modified-since filter, so the full list is fetched and
+    /// filtered client-side on `created_at`. The API doesn't record when a
+    /// conference was last updated either, so later edits to an older
+    /// conference aren't picked up; compare whole fetches with [`conf::diff`]
+    /// to catch those. Conferences whose `created_at` can't be parsed are
+    /// skipped.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| matches!(conf.created_at_datetime(), Ok(created_at) if created_at >= since))
+            .collect())
+    }
+
+    /// Fetch all conferences and keep those starting today or later, in API
+    /// order.
+    ///
+    /// Unlike [`CFPTime::get_upcoming`], which returns whatever the API
+    /// considers upcoming, this filters the full list on `conf_start_date`
+    /// compared with today's UTC date. Conferences whose start date can't be
+    /// parsed are skipped.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_future_confs(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let today = Utc::now().date_naive();
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| matches!(conf.start_date(), Some(start) if start >= today))
+            .collect())
+    }
+
+    /// Fetch the conferences starting between `start` and `end` inclusive,
+    /// sorted by start date. Conferences whose `conf_start_date` can't be
+    /// parsed are skipped.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_confs_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs: Vec<(NaiveDate, Conf)> = self
+            .get_confs()
+            .await?
+            .into_iter()
+            .filter_map(|conf| Some((conf.start_date()?, conf)))
+            .filter(|(date, _)| (start..=end).contains(date))
+            .collect();
+        confs.sort_by_key(|(date, _)| *date);
+
+        Ok(confs.into_iter().map(|(_, conf)| conf).collect())
+    }
+
+    /// Fetch the conferences starting in `year`, grouped by month (1 to 12)
+    /// of `conf_start_date`.
+    ///
+    /// Months without conferences are left out. Within a month conferences
+    /// are sorted by start date, keeping API order on ties. Conferences whose
+    /// start date can't be parsed are skipped.
+    #[tracing::instrument(skip_all)]
+    pub async fn confs_by_month(
+        &self,
+        year: i32,
+    ) -> Result<BTreeMap<u32, Vec<Conf>>, CFPError> {
+        let mut confs: Vec<(NaiveDate, Conf)> = self
+            .get_confs()
+            .await?
+            .into_iter()
+            .filter_map(|conf| Some((conf.start_date()?, conf)))
+            .filter(|(date, _)| date.year() == year)
+            .collect();
+        confs.sort_by_key(|(date, _)| *date);
+
+        let mut by_month: BTreeMap<u32, Vec<Conf>> = BTreeMap::new();
+        for (date, conf) in confs {
+            by_month.entry(date.month()).or_default().push(conf);
+        }
+
+        Ok(by_month)
+    }
+
+    /// Fetch all conferences keyed by `id`, for lookups and joins.
+    ///
+    /// When the API returns the same id more than once, the record that comes
+    /// last in the response is kept.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_confs_map(
+        &self,
+    ) -> Result<HashMap<i32, Conf>, CFPError> {
+        let confs = self.get_confs().await?;
+
+        Ok(confs.into_iter().map(|conf| (conf.id, conf)).collect())
+    }
+
+    /// Fetch all conferences grouped by country, see [`conf::group_by_country`].
+    #[tracing::instrument(skip_all)]
+    pub async fn confs_by_country(
+        &self,
+    ) -> Result<HashMap<String, Vec<Conf>>, CFPError> {
+        let confs = self.get_confs().await?;
+
+        Ok(group_by_country(&confs))
+    }
+
+    /// Fetch the conferences held in any of `countries`, sorted by ascending
+    /// `conf_start_date` with unparseable start dates last.
+    ///
+    /// Countries are compared ignoring case and extra whitespace, like
+    /// [`CFPTime::get_cfps_by_country`], and the list is fetched only once.
+    #[tracing::instrument(skip_all)]
+    pub async fn get_confs_in_countries(
+        &self,
+        countries: &HashSet<String>,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let countries: HashSet<String> = countries.iter().map(|country| normalize(country)).collect();
+        let mut confs: Vec<Conf> = self
+            .get_confs()
+            .await?
+            .into_iter()
+            .filter(|conf| countries.contains(&normalize(&conf.country)))
+            .collect();
+        sort_by_start_date(&mut confs);
+
+        Ok(confs)
+    }
+
+    /// Fetch all conferences and keep those whose `name` contains `query`,
+    /// ignoring case. Returns an empty `Vec` when nothing matches.
+    #[tracing::instrument(skip_all)]
+    pub async fn find_conf_by_name(
+        &self,
+        query: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let query = query.trim().to_lowercase();
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| conf.name.to_lowercase().contains(&query))
+            .collect())
+    }
+
+    /// Fetch all conferences held in `city`.
+    ///
+    /// Cities are compared ignoring case and surrounding or repeated
+    /// whitespace. With the `unaccent` feature diacritics are ignored too, so
+    /// "Zurich" also finds conferences in "Zürich". Returns an empty `Vec`
+    /// when nothing matches.
+    ///
+    /// ```
+    /// use cfptime::{CFPTime, MockTransport};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let transport = MockTransport::new().with_json("/api/conferences/", r#"[
+    ///     {"id": 1, "name": "A", "cfp_deadline": "", "conf_start_date": "", "city": "Zürich",
+    ///      "country": "Switzerland", "created_at": "", "number_of_days": 1},
+    ///     {"id": 2, "name": "B", "cfp_deadline": "", "conf_start_date": "", "city": "Bern",
+    ///      "country": "Switzerland", "created_at": "", "number_of_days": 1}
+    /// ]"#);
+    /// let cfptime = CFPTime::with_transport(transport);
+    ///
+    /// let found = cfptime.find_by_city(" ZÜRICH ").await.unwrap();
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].id, 1);
+    ///
+    /// let found = cfptime.find_by_city("Zurich").await.unwrap();
+    /// assert_eq!(found.len(), usize::from(cfg!(feature = "unaccent")));
+    /// # }
+    /// ```
+    #[tracing::instrument(skip_all)]
+    pub async fn find_by_city(
+        &self,
+        city: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let city = normalize_place(city);
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| normalize_place(&conf.city) == city)
+            .collect())
+    }
+
+    /// Fetch all conferences and keep those whose `website` contains `query`,
+    /// ignoring case. Returns an empty `Vec` when nothing matches.
+    #[tracing::instrument(skip_all)]
+    pub async fn find_conf_by_website(
+        &self,
+        query: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let query = query.trim().to_lowercase();
+        let confs = self.get_confs().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| conf.website.to_lowercase().contains(&query))
+            .collect())
+    }
+
+    /// Stream conferences one at a time instead of collecting them into a `Vec`.
+    ///
+    /// The API returns a single JSON array, so the list is fetched and parsed
+    /// once and its items are then yielded in order. Requires the `stream` feature.
+    #[cfg(feature = "stream")]
+    pub fn stream_confs(
+        &self,
+    ) -> impl Stream<Item = Result<Conf, CFPError>> + '_ {
+        stream::once(self.get_confs())
+            .map_ok(|confs| stream::iter(confs.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Poll the CFP list every `interval` and yield the CFPs whose id wasn't
+    /// in the previous successful fetch, e.g. for a bot announcing new CFPs.
+    ///
+    /// The first fetch happens immediately and yields every CFP; fetches that
+    /// add nothing yield nothing. A failed fetch yields its error and the
+    /// stream carries on: the wait doubles with each consecutive failure, up
+    /// to 32 times `interval`, and returns to `interval` after a success. The
+    /// client's own retries apply to each fetch as usual. Dropping the stream
+    /// stops polling, including a fetch or wait in progress. An `interval`
+    /// shorter than one second, including zero, is raised to one second so
+    /// the API isn't polled in a tight loop. Requires the `stream` feature.
+    #[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+    pub fn watch(
+        &self,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Vec<Conf>, CFPError>> + '_ {
+        let interval = interval.max(MIN_WATCH_INTERVAL);
+        stream::unfold((Vec::new(), None), move |(mut previous, mut failures): (Vec<Conf>, Option<u32>)| async move {
+            loop {
+                if let Some(failures) = failures {
+                    let backoff = 1 << failures.min(MAX_WATCH_BACKOFF_EXP);
+                    tokio::time::sleep(interval.saturating_mul(backoff)).await;
+                }
+
+                match self.get_cfps().await {
+                    Ok(cfps) => {
+                        failures = Some(0);
+                        let added: HashSet<i32> = conf::diff(&previous, &cfps).added.into_iter().collect();
+                        let new: Vec<Conf> = cfps.iter().filter(|cfp| added.contains(&cfp.id)).cloned().collect();
+                        previous = cfps;
+                        if !new.is_empty() {
+                            return Some((Ok(new), (previous, failures)));
+                        }
+                    }
+                    Err(err) => {
+                        failures = Some(failures.map_or(1, |failures| failures + 1));
+                        return Some((Err(err), (previous, failures)));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Check that the API is reachable, e.g. for readiness checks.
+    ///
+    /// Sends a `HEAD` request to the API root (the configured endpoint, by
+    /// default `https://api.cfptime.org/api/`), so no conference data is
+    /// downloaded. Any 2xx status is a success.
+    #[tracing::instrument(skip_all, fields(path = "", status))]
+    pub async fn ping(&self) -> Result<(), CFPError> {
+        let request = self.request(Method::HEAD, String::new(), ())?;
+
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
+        if !resp.status().is_success() {
+            return Err(CFPError::from_response(resp, self.max_response_size).await);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all, fields(path = "upcoming", status, count))]
     pub async fn get_upcoming(
         &self,
-    ) -> Result<Vec<Conf>> {
-        let request = self.request(
+    ) -> Result<Vec<Conf>, CFPError> {
+        self.get_list("upcoming").await
+    }
+
+    /// Fetch the upcoming conferences sorted by ascending `conf_start_date`,
+    /// with unparseable start dates last.
+    ///
+    /// Conferences starting on the same day keep the server's order, see
+    /// [`conf::sort_by_start_date`].
+    #[tracing::instrument(skip_all)]
+    pub async fn get_upcoming_sorted(
+        &self,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let mut confs = self.get_upcoming().await?;
+        sort_by_start_date(&mut confs);
+
+        Ok(confs)
+    }
+
+    /// Fetch all CFPs unless they changed since the last successful fetch.
+    ///
+    /// Sends `If-None-Match` with the ETag remembered from the previous
+    /// response to `cfps` and returns [`Conditional::NotModified`] when the
+    /// server replies 304. This always goes to the API, bypassing the cache.
+    #[tracing::instrument(skip_all, fields(path = "cfps", status, count))]
+    pub async fn get_cfps_if_modified(
+        &self,
+    ) -> Result<Conditional<Vec<Conf>>, CFPError> {
+        self.fetch_list_if_none_match("cfps", self.etag("cfps")).await
+    }
+
+    /// ETag of the last successful response from a list endpoint, e.g. `"cfps"`,
+    /// `"conferences"` or `"upcoming"`.
+    pub fn etag(&self, path: &str) -> Option<String> {
+        self.etags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(path)
+            .cloned()
+    }
+
+    /// Invalidate the response cache so the next call to each list method
+    /// fetches fresh data from the API. Does nothing when caching is disabled.
+    pub fn refresh(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Stop using this client, e.g. when the service shuts down.
+    ///
+    /// Consuming `self` makes any later use a compile error. Requests and
+    /// streams borrow the client, so none can still be in flight when this is
+    /// called; there is nothing to wait for or cancel. Clones share the
+    /// connection pool, which is closed once the last of them is dropped or
+    /// closed, and requests made through other clones are unaffected.
+    pub fn close(self) {
+        drop(self);
+    }
+
+    // Fetch a list endpoint, going through the cache when it's enabled, and
+    // record the number of items on the caller's span. Without the cache,
+    // concurrent calls are coalesced into one request instead, except on
+    // wasm32 where the requests can't be shared.
+    async fn get_list(
+        &self,
+        path: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        let confs = match &self.cache {
+            Some(cache) => cache.get_or_fetch(path, || self.fetch_list(path)).await?,
+            #[cfg(not(target_arch = "wasm32"))]
+            None => self.flights.run(path, || self.shared_fetch_list(path)).await?,
+            #[cfg(target_arch = "wasm32")]
+            None => self.fetch_list(path).await?,
+        };
+        Span::current().record("count", confs.len());
+
+        Ok(confs)
+    }
+
+    async fn fetch_list(
+        &self,
+        path: &str,
+    ) -> Result<Vec<Conf>, CFPError> {
+        match self.fetch_list_if_none_match(path, None).await? {
+            Conditional::Modified(confs) => Ok(confs),
+            Conditional::NotModified => unreachable!("304 is only accepted with If-None-Match"),
+        }
+    }
+
+    // `fetch_list` as a `'static` future that concurrent callers can share.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn shared_fetch_list(
+        &self,
+        path: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<Conf>, CFPError>> + Send + 'static {
+        let client = self.clone();
+        let path = path.to_string();
+
+        async move { client.fetch_list(&path).await }
+    }
+
+    // Send a list request and decode its response as a page.
+    async fn fetch_page(
+        &self,
+        request: Request,
+    ) -> Result<Paginated<Conf>, CFPError> {
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
+        match resp.status() {
+            StatusCode::OK => (),
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
+            }
+        };
+
+        let page = decode_list(resp, self.max_response_size).await?;
+        Span::current().record("count", page.results.len());
+
+        Ok(page)
+    }
+
+    // Fetch a list endpoint, sending `If-None-Match` when an ETag is given and
+    // remembering the ETag of a fully successful fetch.
+    async fn fetch_list_if_none_match(
+        &self,
+        path: &str,
+        etag: Option<String>,
+    ) -> Result<Conditional<Vec<Conf>>, CFPError> {
+        let mut request = self.request(
             Method::GET,
-            "upcoming".to_string(),
-            ()
+            path.to_string(),
+            (),
         )?;
+        // Stored ETags come from `HeaderValue::to_str`, so they are always valid.
+        if let Some(value) = etag.as_deref().and_then(|etag| header::HeaderValue::from_str(etag).ok()) {
+            request.headers_mut().insert(header::IF_NONE_MATCH, value);
+        }
 
-        let resp = self.http_client.execute(request).await?;
+        let resp = self.transport.execute(request).await?;
+        Span::current().record("status", resp.status().as_u16());
         match resp.status() {
             StatusCode::OK => (),
-            s => {
-                bail!("status code: {}, body: {:?}", s, resp.text().await?);
+            StatusCode::NOT_MODIFIED if etag.is_some() => return Ok(Conditional::NotModified),
+            _ => {
+                return Err(CFPError::from_response(resp, self.max_response_size).await);
             }
         };
 
-        let upcoming: Vec<Conf> = resp.json().await?;
+        let new_etag = resp
+            .headers()
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let url = resp.url().to_string();
+        let page = decode_list(resp, self.max_response_size).await?;
+        let confs = self.follow_pages(&url, page).await?;
+        Span::current().record("count", confs.len());
 
-        Ok(upcoming)
+        // Only remembered once every page is decoded, so a failed fetch isn't
+        // answered with `NotModified` next time.
+        if let Some(etag) = new_etag {
+            self.etags
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(path.to_string(), etag);
+        }
+
+        Ok(Conditional::Modified(confs))
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Conf {
-    pub id: i32,
-    pub name: String,
-    pub cfp_deadline: String,
-    pub conf_start_date: String,
-    pub city: String,
-    pub province: String,
-    pub country: String,
-    pub twitter: String,
-    pub website: String,
-    pub cfp_details: String,
-    pub speaker_benefits: String,
-    pub code_of_conduct: String,
-    pub created_at: String,
-    pub number_of_days: i32,
+impl Default for CFPTime {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-pub struct CFPError {
-    pub status_code: StatusCode,
-    pub body: String,
+/// Builder for a [`CFPTime`] client, created with [`CFPTime::builder`].
+///
+/// On `wasm32` requests go through the browser's `fetch`, which handles
+/// timeouts, compression and the User-Agent itself, and there is no retry
+/// middleware: [`CFPTimeBuilder::retry_policy`], [`CFPTimeBuilder::on_retry`],
+/// [`CFPTimeBuilder::proxy`] and the connection pool options don't exist there
+/// and the other HTTP options are ignored.
+#[derive(Debug, Clone)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct CFPTimeBuilder {
+    endpoint: String,
+    max_retries: u32,
+    #[cfg(not(target_arch = "wasm32"))]
+    retry_policy: Option<ExponentialBackoff>,
+    retries: bool,
+    jitter: bool,
+    max_elapsed_time: Duration,
+    max_response_size: usize,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    cache_ttl: Option<Duration>,
+    client: Option<reqwest::Client>,
+    #[cfg(not(target_arch = "wasm32"))]
+    proxies: Vec<reqwest::Proxy>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pool_max_idle_per_host: Option<usize>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pool_idle_timeout: Option<Option<Duration>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    http2_prior_knowledge: bool,
+    transport: Option<Arc<dyn HttpTransport>>,
+    token: Option<Token>,
+    compression: bool,
+    default_headers: header::HeaderMap,
+    #[cfg(not(target_arch = "wasm32"))]
+    on_retry: Option<OnRetry>,
 }
 
-impl fmt::Display for CFPError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "CFPError: status code -> {}, body -> {}",
-            self.status_code.to_string(),
-            self.body
-        )
+impl Default for CFPTimeBuilder {
+    fn default() -> Self {
+        Self {
+            endpoint: ENDPOINT.to_string(),
+            max_retries: 3,
+            #[cfg(not(target_arch = "wasm32"))]
+            retry_policy: None,
+            retries: true,
+            jitter: true,
+            max_elapsed_time: DEFAULT_MAX_ELAPSED_TIME,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            timeout: Some(DEFAULT_TIMEOUT),
+            user_agent: None,
+            cache_ttl: None,
+            client: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            proxies: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            pool_max_idle_per_host: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            pool_idle_timeout: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            http2_prior_knowledge: false,
+            transport: None,
+            token: None,
+            compression: true,
+            default_headers: header::HeaderMap::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            on_retry: None,
+        }
     }
 }
 
-impl fmt::Debug for CFPError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "CFPError: status code -> {}, body -> {}",
-            self.status_code.to_string(),
-            self.body
-        )
+impl CFPTimeBuilder {
+    /// Base URL of the API, defaults to `https://api.cfptime.org/api/`.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Maximum number of retries on transient errors, defaults to 3.
+    ///
+    /// A 429 Too Many Requests response with a `Retry-After` of up to a minute
+    /// is retried after waiting as long as the server asked, other transient
+    /// errors use exponential backoff. When retries are exhausted a 429 is
+    /// returned as [`CFPError::RateLimited`].
+    ///
+    /// Transient errors are only retried for idempotent methods such as GET,
+    /// HEAD and DELETE. A POST, e.g. [`CFPTime::create_conf`], is sent once,
+    /// since it may have been processed before the error; only a 429 with
+    /// `Retry-After`, which the server rejected outright, is sent again.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Custom backoff policy for retrying transient errors, replacing the
+    /// default exponential backoff and [`CFPTimeBuilder::max_retries`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn retry_policy(mut self, retry_policy: ExponentialBackoff) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Don't retry at all, transient errors are returned immediately.
+    /// [`CFPTimeBuilder::max_elapsed_time`] still caps each request.
+    pub fn disable_retries(mut self) -> Self {
+        self.retries = false;
+        self
+    }
+
+    /// Call `on_retry` before each retry of a transient error, e.g. to count
+    /// retries in a metric or warn that the API is flaky.
+    ///
+    /// It gets the number of the upcoming retry, starting at 1, and the
+    /// status code or error of the attempt that failed. It runs inside the
+    /// request's tracing span, just before the retry middleware logs its own
+    /// warning and sleeps. Waits on a 429 `Retry-After` aren't reported.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_retry<F>(mut self, on_retry: F) -> Self
+    where
+        F: Fn(u32, Result<StatusCode, &reqwest_middleware::Error>) + Send + Sync + 'static,
+    {
+        self.on_retry = Some(OnRetry(Arc::new(on_retry)));
+        self
+    }
+
+    /// Randomize the wait between retries, enabled by default.
+    ///
+    /// Jitter spreads out retries from many clients after a shared outage.
+    /// Without it the n-th retry waits exactly the policy's minimum interval
+    /// times its backoff exponent to the power of n, capped at its maximum
+    /// interval.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Maximum time for a request including all of its retries and
+    /// rate-limit waits, defaults to 2 minutes.
+    ///
+    /// A request still running when it runs out fails with
+    /// [`CFPError::DeadlineExceeded`]. The budget complements
+    /// [`CFPTimeBuilder::timeout`], which applies to each attempt.
+    pub fn max_elapsed_time(mut self, max_elapsed_time: Duration) -> Self {
+        self.max_elapsed_time = max_elapsed_time;
+        self
+    }
+
+    /// Largest response body to read, in bytes, defaults to 10 MiB.
+    ///
+    /// The body is read as it arrives and the request fails with
+    /// [`CFPError::ResponseTooLarge`] as soon as it exceeds the cap, so a
+    /// broken or hostile endpoint can't exhaust memory. The cap applies to
+    /// the decompressed body; raise it if the API's lists outgrow it.
+    pub fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Total timeout for each request, defaults to 30 seconds. Requests that
+    /// exceed it fail with [`CFPError::Timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// User-Agent header sent with each request, defaults to
+    /// `cfptime-rs/{version}` so API operators can identify the traffic.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Ask for gzip or brotli compressed responses and decompress them
+    /// transparently, enabled by default.
+    ///
+    /// Disabling it can help when inspecting raw traffic while debugging.
+    pub fn compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Send requests through `proxy`, e.g. `reqwest::Proxy::https("http://proxy:8080")`.
+    ///
+    /// Can be called several times to add more proxies, e.g. one for HTTP and
+    /// one for HTTPS; the first proxy matching a request's URL is used.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Keep at most `max` idle connections per host in the pool, unlimited by
+    /// default.
+    ///
+    /// The default suits occasional use; a service polling at a high rate
+    /// from many tasks may want to bound it, e.g. to the number of tasks.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
     }
+
+    /// Close pooled connections that have been idle for `timeout`, defaults
+    /// to 90 seconds. `None` keeps them open indefinitely.
+    ///
+    /// Set it above the polling interval so each poll reuses the previous
+    /// connection instead of paying for a new TLS handshake.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pool_idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.pool_idle_timeout = Some(timeout.into());
+        self
+    }
+
+    /// Speak HTTP/2 from the first byte instead of negotiating it, disabled
+    /// by default.
+    ///
+    /// Only enable it for an endpoint known to support HTTP/2, e.g. a proxy
+    /// in front of the API; requests to an HTTP/1-only server fail. Over TLS
+    /// HTTP/2 is already used when the server offers it.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http2_prior_knowledge = true;
+        self
+    }
+
+    /// Cache the last successful response of each list endpoint for `ttl`,
+    /// disabled by default. Concurrent calls during a cache miss share a
+    /// single request.
+    pub fn cache(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Use an existing `reqwest::Client` instead of building a new one.
+    ///
+    /// The tracing and retry middleware are layered on top of it as usual, but
+    /// options that configure the `reqwest::Client` itself, such as
+    /// [`CFPTimeBuilder::timeout`], [`CFPTimeBuilder::user_agent`],
+    /// [`CFPTimeBuilder::proxy`], [`CFPTimeBuilder::compression`] and the
+    /// connection pool options, are ignored and must be set on `client`
+    /// instead.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send requests through `transport` instead of the built-in reqwest client.
+    ///
+    /// All HTTP client and middleware options, including retries, are ignored;
+    /// `transport` is responsible for them.
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Authenticate every request with `Authorization: Bearer {token}`.
+    ///
+    /// The token is marked as sensitive and never shows up in `Debug` output
+    /// or tracing spans. Building fails with [`CFPError::InvalidHeader`] if it
+    /// contains characters that aren't allowed in a header.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(Token(token.into()));
+        self
+    }
+
+    /// Send the `name: value` header with every request, e.g. a tenant id for
+    /// an API gateway.
+    ///
+    /// Can be called several times; calling it again with the same name adds
+    /// another value rather than replacing the first. A `Content-Type` given
+    /// here replaces the default JSON one, while the token set with
+    /// [`CFPTimeBuilder::token`] always takes precedence over an
+    /// `Authorization` header.
+    pub fn default_header(mut self, name: header::HeaderName, value: header::HeaderValue) -> Self {
+        self.default_headers.append(name, value);
+        self
+    }
+
+    /// Build the CFPTime client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the underlying HTTP client can't be built, see [`CFPTimeBuilder::try_build`].
+    pub fn build(self) -> CFPTime {
+        match self.try_build() {
+            Ok(client) => client,
+            Err(err) => panic!("creating client failed: {err:?}"),
+        }
+    }
+
+    /// Build the CFPTime client, returning an error if the underlying HTTP
+    /// client can't be built.
+    pub fn try_build(self) -> Result<CFPTime, CFPError> {
+        let transport = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(self.build_http_client()?),
+        };
+        let auth = match &self.token {
+            Some(Token(token)) => {
+                let mut auth = header::HeaderValue::try_from(format!("Bearer {}", token))?;
+                auth.set_sensitive(true);
+                Some(auth)
+            }
+            None => None,
+        };
+
+        Ok(CFPTime {
+            transport,
+            endpoint: self.endpoint,
+            cache: self.cache_ttl.map(|ttl| Arc::new(Cache::new(ttl))),
+            etags: Arc::default(),
+            auth,
+            default_headers: self.default_headers,
+            #[cfg(not(target_arch = "wasm32"))]
+            flights: Arc::default(),
+            max_response_size: self.max_response_size,
+        })
+    }
+
+    // Build the reqwest client and layer the tracing and retry middleware on it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_http_client(&self) -> Result<reqwest_middleware::ClientWithMiddleware, CFPError> {
+        let lclient = match &self.client {
+            Some(client) => client.clone(),
+            None => {
+                let mut http = reqwest::Client::builder()
+                    .gzip(self.compression)
+                    .brotli(self.compression)
+                    .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+                if let Some(timeout) = self.timeout {
+                    http = http.timeout(timeout);
+                }
+                for proxy in &self.proxies {
+                    http = http.proxy(proxy.clone());
+                }
+                if let Some(max) = self.pool_max_idle_per_host {
+                    http = http.pool_max_idle_per_host(max);
+                }
+                if let Some(timeout) = self.pool_idle_timeout {
+                    http = http.pool_idle_timeout(timeout);
+                }
+                if self.http2_prior_knowledge {
+                    http = http.http2_prior_knowledge();
+                }
+                http.build()?
+            }
+        };
+        let mut client = reqwest_middleware::ClientBuilder::new(lclient)
+            .with(reqwest_tracing::TracingMiddleware::default());
+        #[cfg(feature = "metrics")]
+        {
+            client = client.with(metrics::MetricsMiddleware);
+        }
+        client = client.with(ElapsedLimit {
+            max_elapsed_time: self.max_elapsed_time,
+        });
+        if self.retries {
+            let retry_policy = self
+                .retry_policy
+                .unwrap_or_else(|| ExponentialBackoff::builder().build_with_max_retries(self.max_retries));
+            client = client
+                .with(IdempotentOnly(reqwest_retry::RetryTransientMiddleware::new_with_policy_and_strategy(
+                    Backoff {
+                        policy: retry_policy,
+                        jitter: self.jitter,
+                    },
+                    RateLimitStrategy,
+                )));
+            if let Some(on_retry) = &self.on_retry {
+                client = client.with(RetryObserver {
+                    on_retry: on_retry.clone(),
+                    max_retries: retry_policy.max_n_retries,
+                });
+            }
+            client = client
+                .with(RateLimitMiddleware {
+                    max_retries: retry_policy.max_n_retries,
+                });
+        }
+
+        Ok(client.build())
+    }
+
+    // Build the browser-backed reqwest client, the middleware needs a native runtime.
+    #[cfg(target_arch = "wasm32")]
+    fn build_http_client(&self) -> Result<reqwest::Client, CFPError> {
+        match &self.client {
+            Some(client) => Ok(client.clone()),
+            None => Ok(reqwest::Client::builder().build()?),
+        }
+    }
+}
+
+// API token, kept out of the builder's `Debug` output.
+#[derive(Clone)]
+struct Token(String);
+
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Token(..)")
+    }
+}
+
+/// Result of a conditional request, see [`CFPTime::get_cfps_if_modified`].
+#[derive(Debug, Clone)]
+pub enum Conditional<T> {
+    /// The resource changed and was fetched again.
+    Modified(T),
+    /// The server replied 304, the previously fetched data is still current.
+    NotModified,
+}
+
+/// A page of results, see [`CFPTime::get_cfps_paged`].
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+    /// Zero-based page number.
+    pub page: usize,
+    /// Requested page size.
+    pub per_page: usize,
+    /// Total number of items across all pages.
+    pub total: usize,
+    /// Whether there are items after this page.
+    pub has_more: bool,
+}
+
+/// One page of a DRF-style paginated list, see [`CFPTime::get_cfps_paginated`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct Paginated<T> {
+    /// Total number of items across all pages, if the API reports it.
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// URL of the next page.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// URL of the previous page.
+    #[serde(default)]
+    pub previous: Option<String>,
+    /// Items on this page.
+    pub results: Vec<T>,
+}
+
+// Website of `conf` reduced to its lowercase host, without `www.`, and path,
+// without a trailing slash, for comparing records.
+fn website_key(conf: &Conf) -> Option<String> {
+    let url = conf.website_url()?;
+    let host = url.host_str()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+
+    Some(format!("{}{}", host, url.path().trim_end_matches('/').to_lowercase()))
+}
+
+// Decode a list response, see `decode_list_body`.
+async fn decode_list(resp: reqwest::Response, max_size: usize) -> Result<Paginated<Conf>, CFPError> {
+    let content_type = content_type(&resp);
+    let url = resp.url().to_string();
+    let body = read_body(resp, max_size, |_| ()).await?;
+
+    decode_list_body(content_type, url, &body)
 }
 
-// This is important for other errors to wrap this one.
-impl error::Error for CFPError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        // Generic error, underlying cause isn't tracked.
-        None
+// Decode a list body that is either a bare JSON array or an envelope object
+// with the items under `results`, which is returned as is. An empty body, seen
+// from the API during deploys, is an empty list.
+fn decode_list_body(content_type: Option<String>, url: String, body: &[u8]) -> Result<Paginated<Conf>, CFPError> {
+    let results: Vec<Conf> = match body.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'{') => return decode_body(content_type, url, body),
+        Some(_) => decode_body(content_type, url, body)?,
+        None => Vec::new(),
+    };
+    Ok(Paginated {
+        count: Some(results.len()),
+        next: None,
+        previous: None,
+        results,
+    })
+}
+
+// Identify a page by its URL, ignoring a trailing slash on the path and the
+// fragment, so a cursor back to an already fetched page is recognized.
+fn page_key(url: &str) -> String {
+    let Ok(mut url) = Url::parse(url) else {
+        return url.to_string();
+    };
+    url.set_fragment(None);
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+
+    url.into()
+}
+
+// Decode a JSON response body, rejecting responses that declare a non-JSON
+// content type such as an HTML error page from a proxy.
+async fn decode<T>(resp: reqwest::Response, max_size: usize) -> Result<T, CFPError>
+where
+    T: DeserializeOwned,
+{
+    let content_type = content_type(&resp);
+    let url = resp.url().to_string();
+    let body = read_body(resp, max_size, |_| ()).await?;
+
+    decode_body(content_type, url, &body)
+}
+
+// Decode a JSON body already read from a response, see `decode`.
+fn decode_body<T>(content_type: Option<String>, url: String, body: &[u8]) -> Result<T, CFPError>
+where
+    T: DeserializeOwned,
+{
+    if let Some(content_type) = content_type {
+        if !content_type.to_ascii_lowercase().contains("json") {
+            return Err(CFPError::NotJson {
+                url,
+                content_type,
+                snippet: snippet(body),
+            });
+        }
     }
+
+    from_json(url, body)
+}
+
+// Deserialize a JSON body.
+#[cfg(not(feature = "path-to-error"))]
+fn from_json<T>(url: String, body: &[u8]) -> Result<T, CFPError>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_slice(body).map_err(|source| CFPError::DecodeAt {
+        url,
+        path: None,
+        snippet: snippet_at(body, source.line(), source.column()),
+        source: Arc::new(source),
+    })
+}
+
+// Deserialize a JSON body, reporting where it failed.
+#[cfg(feature = "path-to-error")]
+fn from_json<T>(url: String, body: &[u8]) -> Result<T, CFPError>
+where
+    T: DeserializeOwned,
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let (path, result) = match serde_path_to_error::deserialize(&mut deserializer) {
+        Ok(value) => (String::from("."), deserializer.end().map(|()| value)),
+        Err(err) => (err.path().to_string(), Err(err.into_inner())),
+    };
+
+    result.map_err(|source| CFPError::DecodeAt {
+        url,
+        path: Some(path),
+        snippet: snippet_at(body, source.line(), source.column()),
+        source: Arc::new(source),
+    })
+}
+
+// Up to `SNIPPET_LEN` characters of `body` centered on the 1-based `line` and
+// `column` of a JSON error.
+fn snippet_at(body: &[u8], line: usize, column: usize) -> String {
+    let line_start: usize = body
+        .split(|b| *b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(|line| line.len() + 1)
+        .sum();
+    let offset = (line_start + column.saturating_sub(1)).min(body.len());
+
+    snippet(&body[offset.saturating_sub(SNIPPET_LEN / 2)..])
+}
+
+// Read a response body chunk by chunk, calling `on_chunk` with the number of
+// bytes read so far after each one, and fail once it exceeds `max_size`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn read_body(
+    mut resp: reqwest::Response,
+    max_size: usize,
+    on_chunk: impl Fn(usize),
+) -> Result<Vec<u8>, CFPError> {
+    check_content_length(&resp, max_size)?;
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > max_size {
+            return Err(too_large(&resp, max_size));
+        }
+        body.extend_from_slice(&chunk);
+        on_chunk(body.len());
+    }
+
+    Ok(body)
+}
+
+// The wasm client can only read the body at once, so `on_chunk` is called
+// once and a body without `Content-Length` is only checked when complete.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn read_body(
+    resp: reqwest::Response,
+    max_size: usize,
+    on_chunk: impl Fn(usize),
+) -> Result<Vec<u8>, CFPError> {
+    check_content_length(&resp, max_size)?;
+    let url = resp.url().to_string();
+    let body = resp.bytes().await?.to_vec();
+    if body.len() > max_size {
+        return Err(CFPError::ResponseTooLarge { url, limit: max_size });
+    }
+    on_chunk(body.len());
+
+    Ok(body)
+}
+
+// Fail early when the declared `Content-Length` is already over `max_size`.
+fn check_content_length(resp: &reqwest::Response, max_size: usize) -> Result<(), CFPError> {
+    match resp.content_length() {
+        Some(len) if len > max_size as u64 => Err(too_large(resp, max_size)),
+        _ => Ok(()),
+    }
+}
+
+fn too_large(resp: &reqwest::Response, max_size: usize) -> CFPError {
+    CFPError::ResponseTooLarge {
+        url: resp.url().to_string(),
+        limit: max_size,
+    }
+}
+
+// The `Content-Type` header of a response, if present and valid.
+fn content_type(resp: &reqwest::Response) -> Option<String> {
+    resp.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+// Append `path` to the `endpoint` URL.
+//
+// Unlike a bare `Url::join`, a missing trailing slash on the endpoint doesn't
+// drop its last segment, and empty segments from leading, repeated or doubled
+// slashes in either part are removed. A trailing slash on `path`, or on the
+// endpoint when `path` is empty, is kept.
+fn join_url(endpoint: &str, path: &str) -> Result<Url, CFPError> {
+    let mut url = Url::parse(endpoint)?;
+    let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    };
+
+    let trailing_slash = if path.is_empty() {
+        url.path().ends_with('/')
+    } else {
+        path.ends_with('/')
+    };
+    let segments: Vec<&str> = url
+        .path()
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let mut joined = format!("/{}", segments.join("/"));
+    if trailing_slash && !segments.is_empty() {
+        joined.push('/');
+    }
+    url.set_path(&joined);
+    url.set_query(query);
+
+    Ok(url)
+}
+
+// First few hundred characters of a response body, for error messages.
+fn snippet(body: &[u8]) -> String {
+    String::from_utf8_lossy(body).chars().take(SNIPPET_LEN).collect()
 }
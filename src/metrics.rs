@@ -0,0 +1,56 @@
+// Request counters and latency histogram, recorded through the `metrics`
+// facade for whatever recorder the application installs.
+use std::time::Instant;
+
+use reqwest::{Request, Response, Url};
+use reqwest_middleware::{Middleware, Next};
+use task_local_extensions::Extensions;
+
+// Counter of finished requests, labelled by `endpoint` and `status`.
+const REQUESTS_TOTAL: &str = "cfptime_requests_total";
+
+// Histogram of request latencies in seconds, labelled by `endpoint`.
+const REQUEST_DURATION_SECONDS: &str = "cfptime_request_duration_seconds";
+
+// Records each request as the caller sees it, retries and rate-limit waits
+// included.
+pub(crate) struct MetricsMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let endpoint = endpoint(req.url());
+        let start = Instant::now();
+        let result = next.run(req, extensions).await;
+
+        let status = match &result {
+            Ok(resp) => resp.status().as_str().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        ::metrics::histogram!(REQUEST_DURATION_SECONDS, start.elapsed().as_secs_f64(), "endpoint" => endpoint.clone());
+        ::metrics::increment_counter!(REQUESTS_TOTAL, "endpoint" => endpoint, "status" => status);
+
+        result
+    }
+}
+
+// URL path with numeric segments replaced by `{id}`, so `/api/cfps/5/` and
+// `/api/cfps/6/` share the `/api/cfps/{id}/` label.
+fn endpoint(url: &Url) -> String {
+    url.path()
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,115 @@
+//! Fluent, composable filters over the conference list, see [`CFPTime::query`].
+use chrono::NaiveDate;
+
+use crate::conf::normalize;
+use crate::{CFPError, CFPTime, Conf};
+
+/// Query over the CFP list, created with [`CFPTime::query`].
+///
+/// Each method adds a criterion and a CFP has to match all of them. The API
+/// has no query parameters, so [`ConfQuery::fetch`] downloads the full list
+/// and filters it client-side, keeping the API's order.
+///
+/// ```no_run
+/// # use cfptime::CFPTime;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), cfptime::CFPError> {
+/// let client = CFPTime::new();
+/// let confs = client
+///     .query()
+///     .country("France")
+///     .open_only()
+///     .limit(10)
+///     .fetch()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ConfQuery<'a> {
+    client: &'a CFPTime,
+    country: Option<String>,
+    open_only: bool,
+    code_of_conduct: bool,
+    starting_after: Option<NaiveDate>,
+    limit: Option<usize>,
+}
+
+impl<'a> ConfQuery<'a> {
+    pub(crate) fn new(client: &'a CFPTime) -> Self {
+        Self {
+            client,
+            country: None,
+            open_only: false,
+            code_of_conduct: false,
+            starting_after: None,
+            limit: None,
+        }
+    }
+
+    /// Keep CFPs whose `country` equals `country`, ignoring case and
+    /// surrounding or repeated whitespace.
+    pub fn country(mut self, country: impl AsRef<str>) -> Self {
+        self.country = Some(normalize(country.as_ref()));
+        self
+    }
+
+    /// Keep CFPs that are still accepting submissions, see [`Conf::is_open`].
+    pub fn open_only(mut self) -> Self {
+        self.open_only = true;
+        self
+    }
+
+    /// Keep conferences that advertise a code of conduct, see
+    /// [`Conf::has_code_of_conduct`].
+    pub fn with_code_of_conduct(mut self) -> Self {
+        self.code_of_conduct = true;
+        self
+    }
+
+    /// Keep conferences starting strictly after `date`. Conferences whose
+    /// start date can't be parsed are dropped.
+    pub fn starting_after(mut self, date: NaiveDate) -> Self {
+        self.starting_after = Some(date);
+        self
+    }
+
+    /// Return at most `limit` CFPs, the first ones matching in API order.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether `conf` matches every criterion of this query.
+    pub fn matches(&self, conf: &Conf) -> bool {
+        if let Some(country) = &self.country {
+            if normalize(&conf.country) != *country {
+                return false;
+            }
+        }
+        if self.open_only && !conf.is_open() {
+            return false;
+        }
+        if self.code_of_conduct && !conf.has_code_of_conduct() {
+            return false;
+        }
+        if let Some(date) = self.starting_after {
+            if conf.start_date().is_none_or(|start| start <= date) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Fetch the CFPs and apply the query.
+    pub async fn fetch(self) -> Result<Vec<Conf>, CFPError> {
+        let confs = self.client.get_cfps().await?;
+
+        Ok(confs
+            .into_iter()
+            .filter(|conf| self.matches(conf))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
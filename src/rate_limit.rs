@@ -0,0 +1,88 @@
+// Retry-After aware handling of 429 Too Many Requests responses.
+//
+// Only `retry_after` is available on wasm32, where there is no middleware.
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header;
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    anyhow::anyhow,
+    reqwest::{Request, Response, StatusCode},
+    reqwest_middleware::{Middleware, Next},
+    reqwest_retry::{default_on_request_failure, default_on_request_success, Retryable, RetryableStrategy},
+    task_local_extensions::Extensions,
+};
+
+// Longest Retry-After we are willing to wait for, anything longer is returned
+// to the caller straight away.
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+// Parse the `Retry-After` header, given either as a number of seconds or as an
+// HTTP date.
+pub(crate) fn retry_after(headers: &header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+// Waits for `Retry-After` before retrying a 429, up to `max_retries` times.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct RateLimitMiddleware {
+    pub(crate) max_retries: u32,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let mut retries = 0;
+        loop {
+            let request = req.try_clone().ok_or_else(|| {
+                reqwest_middleware::Error::Middleware(anyhow!("request with a streaming body can't be retried"))
+            })?;
+            let resp = next.clone().run(request, extensions).await?;
+            if resp.status() != StatusCode::TOO_MANY_REQUESTS || retries >= self.max_retries {
+                return Ok(resp);
+            }
+
+            match retry_after(resp.headers()) {
+                Some(wait) if wait <= MAX_RETRY_AFTER => {
+                    tracing::warn!("Rate limited, retry attempt #{}. Sleeping {:?} as requested", retries, wait);
+                    tokio::time::sleep(wait).await;
+                    retries += 1;
+                }
+                _ => return Ok(resp),
+            }
+        }
+    }
+}
+
+// Default retry classification, except that 429 responses carrying a
+// `Retry-After` are left to `RateLimitMiddleware` instead of being retried
+// with blind exponential backoff.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct RateLimitStrategy;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RetryableStrategy for RateLimitStrategy {
+    fn handle(&self, res: &reqwest_middleware::Result<Response>) -> Option<Retryable> {
+        match res {
+            Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS && retry_after(resp.headers()).is_some() => {
+                Some(Retryable::Fatal)
+            }
+            Ok(resp) => default_on_request_success(resp),
+            Err(err) => default_on_request_failure(err),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// Backoff policy and total time budget for the retry middleware.
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::{Method, Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::{RetryPolicy, Retryable, RetryableStrategy};
+use retry_policies::RetryDecision;
+use task_local_extensions::Extensions;
+
+use crate::rate_limit::RateLimitStrategy;
+
+// `ExponentialBackoff`, optionally without its random jitter.
+pub(crate) struct Backoff {
+    pub(crate) policy: ExponentialBackoff,
+    pub(crate) jitter: bool,
+}
+
+impl RetryPolicy for Backoff {
+    fn should_retry(&self, n_past_retries: u32) -> RetryDecision {
+        if self.jitter {
+            return self.policy.should_retry(n_past_retries);
+        }
+        if n_past_retries >= self.policy.max_n_retries {
+            return RetryDecision::DoNotRetry;
+        }
+
+        let factor = self
+            .policy
+            .backoff_exponent
+            .checked_pow(n_past_retries)
+            .unwrap_or(u32::MAX);
+        let wait = self
+            .policy
+            .min_retry_interval
+            .saturating_mul(factor)
+            .min(self.policy.max_retry_interval);
+        let execute_after = Utc::now() + chrono::Duration::from_std(wait).unwrap_or_else(|_| chrono::Duration::zero());
+
+        RetryDecision::Retry { execute_after }
+    }
+}
+
+// Applies the retry middleware to idempotent requests only, so a request
+// with side effects, such as the POST of `create_conf`, is never replayed
+// after a transient error that may have happened after the server acted on it.
+pub(crate) struct IdempotentOnly<M>(pub(crate) M);
+
+#[async_trait::async_trait]
+impl<M: Middleware> Middleware for IdempotentOnly<M> {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if is_idempotent(req.method()) {
+            self.0.handle(req, extensions, next).await
+        } else {
+            next.run(req, extensions).await
+        }
+    }
+}
+
+// Whether repeating a request with `method` has the same effect as sending it
+// once, per RFC 9110.
+pub(crate) fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+    )
+}
+
+// Fails a request, including all of its retries and rate-limit waits, once
+// `max_elapsed_time` has passed.
+pub(crate) struct ElapsedLimit {
+    pub(crate) max_elapsed_time: Duration,
+}
+
+#[async_trait::async_trait]
+impl Middleware for ElapsedLimit {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let url = req.url().to_string();
+        match tokio::time::timeout(self.max_elapsed_time, next.run(req, extensions)).await {
+            Ok(result) => result,
+            Err(_) => Err(reqwest_middleware::Error::Middleware(anyhow::Error::new(
+                DeadlineExceeded {
+                    url,
+                    elapsed: self.max_elapsed_time,
+                },
+            ))),
+        }
+    }
+}
+
+// Returned by `ElapsedLimit` through the middleware chain, and turned into
+// `CFPError::DeadlineExceeded` on the way out.
+#[derive(Debug)]
+pub(crate) struct DeadlineExceeded {
+    pub(crate) url: String,
+    pub(crate) elapsed: Duration,
+}
+
+impl std::fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "request and its retries didn't complete within {:?}", self.elapsed)
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+// Callback set with `CFPTimeBuilder::on_retry`, kept out of the builder's
+// `Debug` output.
+#[derive(Clone)]
+pub(crate) struct OnRetry(pub(crate) Arc<dyn Fn(u32, Outcome<'_>) + Send + Sync>);
+
+// Status code or error of a failed attempt.
+type Outcome<'a> = Result<StatusCode, &'a reqwest_middleware::Error>;
+
+impl std::fmt::Debug for OnRetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OnRetry(..)")
+    }
+}
+
+// Number of retries announced so far for the current request, shared with
+// the retry layer through the request's extensions.
+struct Attempt(u32);
+
+// Sits just inside the retry layer, so it sees every attempt, and calls
+// `on_retry` when an attempt failed transiently and the retry layer is about
+// to back off and try again.
+pub(crate) struct RetryObserver {
+    pub(crate) on_retry: OnRetry,
+    pub(crate) max_retries: u32,
+}
+
+#[async_trait::async_trait]
+impl Middleware for RetryObserver {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if !is_idempotent(req.method()) {
+            return next.run(req, extensions).await;
+        }
+
+        let attempt = extensions.get::<Attempt>().map_or(0, |attempt| attempt.0);
+        let result = next.run(req, extensions).await;
+        if attempt < self.max_retries && matches!(RateLimitStrategy.handle(&result), Some(Retryable::Transient)) {
+            extensions.insert(Attempt(attempt + 1));
+            (self.on_retry.0)(attempt + 1, result.as_ref().map(Response::status));
+        }
+        result
+    }
+}
@@ -0,0 +1,46 @@
+// Coalescing of concurrent requests for the same list endpoint.
+//
+// Not built on wasm32, where the request futures aren't `Send` and storing
+// them would make `CFPTime` neither `Send` nor `Sync`.
+use std::collections::HashMap;
+use std::future::Future;
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use crate::{CFPError, Conf};
+
+// A list request that can be awaited by several callers.
+type Flight = BoxFuture<'static, Result<Vec<Conf>, CFPError>>;
+
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    flights: tokio::sync::Mutex<HashMap<String, Shared<Flight>>>,
+}
+
+impl SingleFlight {
+    // Await the in-flight request for `key`, or start one with `fetch` when
+    // there is none. Every caller gets a clone of the result, error included.
+    pub(crate) async fn run<F, Fut>(&self, key: &str, fetch: F) -> Result<Vec<Conf>, CFPError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<Conf>, CFPError>> + Send + 'static,
+    {
+        let flight = self
+            .flights
+            .lock()
+            .await
+            .entry(key.to_string())
+            .or_insert_with(|| fetch().boxed().shared())
+            .clone();
+        let result = flight.clone().await;
+
+        // The first caller to finish removes the entry, so later calls send a
+        // new request instead of reusing this result.
+        let mut flights = self.flights.lock().await;
+        if flights.get(key).is_some_and(|current| current.ptr_eq(&flight)) {
+            flights.remove(key);
+        }
+
+        result
+    }
+}
@@ -0,0 +1,41 @@
+//! Pluggable HTTP transport, so the client can be exercised without the live API.
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use reqwest::{Request, Response};
+
+use crate::CFPError;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod mock;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use mock::MockTransport;
+
+/// Sends the requests built by [`CFPTime`](crate::CFPTime).
+///
+/// The default transport is the reqwest client with the tracing and retry
+/// middleware layered on it, or the plain reqwest client on `wasm32`, where
+/// the returned futures aren't `Send`.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait HttpTransport: Debug + Send + Sync {
+    /// Send `request` and return the response, whatever its status code.
+    async fn execute(&self, request: Request) -> Result<Response, CFPError>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait]
+impl HttpTransport for reqwest_middleware::ClientWithMiddleware {
+    async fn execute(&self, request: Request) -> Result<Response, CFPError> {
+        Ok(reqwest_middleware::ClientWithMiddleware::execute(self, request).await?)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl HttpTransport for reqwest::Client {
+    async fn execute(&self, request: Request) -> Result<Response, CFPError> {
+        Ok(reqwest::Client::execute(self, request).await?)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use reqwest::{header, Request, Response, StatusCode};
+
+use super::HttpTransport;
+use crate::CFPError;
+
+/// Transport returning canned responses, for testing code built on this crate.
+///
+/// Routes are matched on the request URL path, ignoring trailing slashes, e.g.
+/// `/api/cfps` or `/api/cfps/5`. Unmatched requests get an empty 404 response.
+/// Clones share the same routes and recorded requests.
+///
+/// ```
+/// use cfptime::{CFPTime, MockTransport};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = MockTransport::new().with_json("/api/upcoming/", "[]");
+/// let cfptime = CFPTime::with_transport(transport.clone());
+///
+/// assert!(cfptime.get_upcoming().await.unwrap().is_empty());
+/// assert_eq!(transport.requests(), ["https://api.cfptime.org/api/upcoming"]);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    routes: Arc<Mutex<HashMap<String, (StatusCode, String)>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockTransport {
+    /// Create a mock transport without any routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reply to `path` with 200 OK and the JSON `body`.
+    pub fn with_json(self, path: &str, body: impl Into<String>) -> Self {
+        self.with_response(path, StatusCode::OK, body)
+    }
+
+    /// Reply to `path` with `status` and the JSON `body`.
+    pub fn with_response(self, path: &str, status: StatusCode, body: impl Into<String>) -> Self {
+        self.routes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(route(path), (status, body.into()));
+        self
+    }
+
+    /// URLs of the requests executed so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for MockTransport {
+    async fn execute(&self, request: Request) -> Result<Response, CFPError> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request.url().to_string());
+
+        let (status, body) = self
+            .routes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&route(request.url().path()))
+            .cloned()
+            .unwrap_or((StatusCode::NOT_FOUND, String::new()));
+
+        let mut response = http::Response::new(body);
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+
+        Ok(response.into())
+    }
+}
+
+// Normalize a route path so it matches with or without trailing slashes.
+fn route(path: &str) -> String {
+    path.trim_end_matches('/').to_string()
+}
@@ -0,0 +1,677 @@
+use cfptime::{CFPError, CFPTime, Conditional};
+use reqwest::StatusCode;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RUSTCONF: &str = r#"{
+    "id": 42,
+    "name": "RustConf",
+    "cfp_deadline": "2030-04-01",
+    "conf_start_date": "2030-09-10",
+    "city": "Portland",
+    "province": "Oregon",
+    "country": "USA",
+    "twitter": "@rustconf",
+    "website": "https://rustconf.com",
+    "cfp_details": "",
+    "speaker_benefits": "Travel, Hotel",
+    "code_of_conduct": "https://rustconf.com/conduct",
+    "created_at": "2030-01-15T09:30:00Z",
+    "number_of_days": 2
+}"#;
+
+const EUROCONF: &str = r#"{
+    "id": 7,
+    "name": "EuroRust",
+    "cfp_deadline": "2030-05-15",
+    "conf_start_date": "2030-10-01",
+    "country": "Belgium",
+    "created_at": "2030-02-01T12:00:00Z",
+    "number_of_days": 1
+}"#;
+
+// Start a mock API and a client pointed at it. Retries are disabled so the
+// error cases fail on the first response.
+async fn setup() -> (MockServer, CFPTime) {
+    let server = MockServer::start().await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .disable_retries()
+        .build();
+
+    (server, client)
+}
+
+async fn stub(server: &MockServer, route: &str, response: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+fn json(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+#[tokio::test]
+async fn cfps_are_parsed() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/cfps", json(format!("[{}, {}]", RUSTCONF, EUROCONF))).await;
+
+    let confs = client.get_cfps().await.unwrap();
+    assert_eq!(confs.len(), 2);
+
+    let rustconf = &confs[0];
+    assert_eq!(rustconf.id, 42);
+    assert_eq!(rustconf.name, "RustConf");
+    assert_eq!(rustconf.cfp_deadline, "2030-04-01");
+    assert_eq!(rustconf.city, "Portland");
+    assert_eq!(rustconf.country, "USA");
+    assert_eq!(rustconf.twitter_handle().as_deref(), Some("rustconf"));
+    assert_eq!(rustconf.number_of_days, 2);
+
+    // Missing optional fields default to empty.
+    assert_eq!(confs[1].name, "EuroRust");
+    assert_eq!(confs[1].city, "");
+}
+
+#[tokio::test]
+async fn conferences_and_single_conference_are_parsed() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/conferences", json(format!("[{}]", EUROCONF))).await;
+    stub(&server, "/api/conferences/42/", json(RUSTCONF.to_string())).await;
+
+    let confs = client.get_confs().await.unwrap();
+    assert_eq!(confs.iter().map(|conf| conf.id).collect::<Vec<_>>(), [7]);
+
+    let conf = client.get_conf(42).await.unwrap();
+    assert_eq!(conf.name, "RustConf");
+    assert_eq!(conf.website_url().unwrap().as_str(), "https://rustconf.com/");
+}
+
+#[tokio::test]
+async fn upcoming_is_parsed() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/upcoming", json(format!("[{}, {}]", EUROCONF, RUSTCONF))).await;
+
+    let ids: Vec<i32> = client
+        .get_upcoming_sorted()
+        .await
+        .unwrap()
+        .iter()
+        .map(|conf| conf.id)
+        .collect();
+    assert_eq!(ids, [42, 7]);
+}
+
+#[tokio::test]
+async fn cfps_are_paged() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/cfps", json(format!("[{}, {}]", RUSTCONF, EUROCONF))).await;
+
+    let page = client.get_cfps_paged(0, 1).await.unwrap();
+    assert_eq!(page.items.iter().map(|conf| conf.id).collect::<Vec<_>>(), [42]);
+    assert_eq!(page.total, 2);
+    assert!(page.has_more);
+
+    let page = client.get_cfps_paged(1, 1).await.unwrap();
+    assert_eq!(page.items.iter().map(|conf| conf.id).collect::<Vec<_>>(), [7]);
+    assert!(!page.has_more);
+
+    let page = client.get_cfps_paged(0, 0).await.unwrap();
+    assert!(page.items.is_empty());
+    assert!(!page.has_more);
+}
+
+#[tokio::test]
+async fn not_found_is_an_api_error() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/cfps/1/", ResponseTemplate::new(404).set_body_string("Not found.")).await;
+
+    match client.get_cfp(1).await {
+        Err(CFPError::Api { url, status, body, .. }) => {
+            assert_eq!(url, format!("{}/api/cfps/1/", server.uri()));
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body, "Not found.");
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn server_error_is_an_api_error() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/conferences", ResponseTemplate::new(500)).await;
+
+    match client.get_confs().await {
+        Err(CFPError::Api { status, .. }) => assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR),
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn malformed_json_is_a_decode_error() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/upcoming", json(r#"[{"id": 1, "name": "#.to_string())).await;
+
+    assert!(matches!(
+        client.get_upcoming().await,
+        Err(CFPError::DecodeAt { .. })
+    ));
+}
+
+#[tokio::test]
+async fn decode_error_reports_the_url() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/conferences/42/", json(r#"{"id": "forty-two"}"#.to_string())).await;
+
+    let err = client.get_conf(42).await.unwrap_err();
+    assert!(matches!(err, CFPError::DecodeAt { .. }), "{:?}", err);
+    assert_eq!(err.url(), Some(format!("{}/api/conferences/42/", server.uri()).as_str()));
+}
+
+#[cfg(feature = "path-to-error")]
+#[tokio::test]
+async fn decode_error_reports_the_json_path() {
+    let (server, client) = setup().await;
+    let body = format!("[{}, {}]", RUSTCONF, EUROCONF.replace(r#""number_of_days": 1"#, r#""number_of_days": "one""#));
+    stub(&server, "/api/cfps", json(body)).await;
+
+    match client.get_cfps().await {
+        Err(CFPError::DecodeAt { path, snippet, .. }) => {
+            assert_eq!(path.as_deref(), Some("[1].number_of_days"));
+            assert!(snippet.contains(r#""number_of_days": "one""#), "{}", snippet);
+        }
+        other => panic!("expected a decode error with a path, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn html_error_page_is_not_json() {
+    let (server, client) = setup().await;
+    stub(
+        &server,
+        "/api/cfps",
+        ResponseTemplate::new(200).set_body_raw("<html>Maintenance</html>", "text/html"),
+    )
+    .await;
+
+    match client.get_cfps().await {
+        Err(CFPError::NotJson { content_type, snippet, .. }) => {
+            assert_eq!(content_type, "text/html");
+            assert_eq!(snippet, "<html>Maintenance</html>");
+        }
+        other => panic!("expected a non-JSON error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn empty_list_body_is_an_empty_list() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/cfps", ResponseTemplate::new(200)).await;
+
+    assert!(client.get_cfps().await.unwrap().is_empty());
+    assert_eq!(client.get_cfps_paginated().await.unwrap().count, Some(0));
+    assert_eq!(client.get_cfps_raw().await.unwrap(), serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn empty_single_body_is_a_decode_error() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/conferences/42/", ResponseTemplate::new(200)).await;
+
+    assert!(matches!(
+        client.get_conf(42).await,
+        Err(CFPError::DecodeAt { .. })
+    ));
+}
+
+#[tokio::test]
+async fn cfp_for_conf_falls_back_to_name_and_website() {
+    let (server, client) = setup().await;
+    stub(&server, "/api/conferences/42/", json(RUSTCONF.to_string())).await;
+    stub(&server, "/api/conferences/7/", json(EUROCONF.to_string())).await;
+    stub(
+        &server,
+        "/api/conferences/8/",
+        json(RUSTCONF.replace(r#""id": 42"#, r#""id": 8"#).replace("RustConf", "RustConf Portland")),
+    )
+    .await;
+    stub(
+        &server,
+        "/api/cfps",
+        json(r#"[
+            {"id": 100, "name": "  rustconf ", "cfp_deadline": "", "conf_start_date": "", "country": "",
+             "created_at": "", "number_of_days": 1},
+            {"id": 200, "name": "Other", "cfp_deadline": "", "conf_start_date": "", "country": "",
+             "website": "www.RustConf.com/", "created_at": "", "number_of_days": 1}
+        ]"#
+        .to_string()),
+    )
+    .await;
+
+    // Same name, ignoring case and whitespace.
+    assert_eq!(client.get_cfp_for_conf(42).await.unwrap().map(|cfp| cfp.id), Some(100));
+    // Same website, ignoring `www.` and the trailing slash.
+    assert_eq!(client.get_cfp_for_conf(8).await.unwrap().map(|cfp| cfp.id), Some(200));
+    assert_eq!(client.get_cfp_for_conf(7).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn concurrent_list_calls_share_one_request() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json(format!("[{}]", RUSTCONF)).set_delay(std::time::Duration::from_millis(200)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let other = client.clone();
+    let (first, second) = tokio::join!(client.get_cfps(), other.get_cfps());
+    assert_eq!(first.unwrap().len(), 1);
+    assert_eq!(second.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn concurrent_list_calls_share_the_error() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(ResponseTemplate::new(404).set_delay(std::time::Duration::from_millis(200)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let other = client.clone();
+    let (first, second) = tokio::join!(client.get_cfps(), other.get_cfps());
+    for result in [first, second] {
+        match result {
+            Err(CFPError::Api { status, .. }) => assert_eq!(status, StatusCode::NOT_FOUND),
+            other => panic!("expected an API error, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn unchanged_cfps_are_not_modified() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .and(header("if-none-match", r#""v1""#))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json(format!("[{}]", RUSTCONF)).insert_header("ETag", r#""v1""#))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    match client.get_cfps_if_modified().await {
+        Ok(Conditional::Modified(confs)) => assert_eq!(confs.len(), 1),
+        other => panic!("expected the CFPs, got {:?}", other),
+    }
+    assert_eq!(client.etag("cfps").as_deref(), Some(r#""v1""#));
+    assert!(matches!(client.get_cfps_if_modified().await, Ok(Conditional::NotModified)));
+}
+
+#[tokio::test]
+async fn concurrent_list_calls_share_a_timeout() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json("[]".to_string()).set_delay(std::time::Duration::from_millis(500)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .timeout(std::time::Duration::from_millis(100))
+        .disable_retries()
+        .build();
+
+    let other = client.clone();
+    let (first, second) = tokio::join!(client.get_cfps(), other.get_cfps());
+    assert!(matches!(first, Err(CFPError::Timeout(_))), "{:?}", first);
+    assert!(matches!(second, Err(CFPError::Timeout(_))), "{:?}", second);
+}
+
+#[tokio::test]
+async fn etag_of_an_undecodable_response_is_not_kept() {
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json("[{".to_string()).insert_header("ETag", r#""v1""#))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .and(header("if-none-match", r#""v1""#))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(0)
+        .mount(&server)
+        .await;
+    stub(&server, "/api/cfps", json(format!("[{}]", RUSTCONF))).await;
+
+    assert!(client.get_cfps_if_modified().await.is_err());
+    assert_eq!(client.etag("cfps"), None);
+    match client.get_cfps_if_modified().await {
+        Ok(Conditional::Modified(confs)) => assert_eq!(confs.len(), 1),
+        other => panic!("expected the CFPs, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn oversized_response_is_rejected() {
+    let server = MockServer::start().await;
+    stub(&server, "/api/cfps", json(format!("[{}]", RUSTCONF))).await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .max_response_size(64)
+        .build();
+
+    match client.get_cfps().await {
+        Err(CFPError::ResponseTooLarge { limit, .. }) => assert_eq!(limit, 64),
+        other => panic!("expected a response too large error, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn watch_yields_only_new_cfps() {
+    use futures::StreamExt;
+
+    let (server, client) = setup().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json(format!("[{}]", RUSTCONF)))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    stub(&server, "/api/cfps", json(format!("[{}, {}]", RUSTCONF, EUROCONF))).await;
+
+    let watch = client.watch(std::time::Duration::from_millis(10));
+    futures::pin_mut!(watch);
+    let ids = |confs: Vec<cfptime::Conf>| confs.iter().map(|conf| conf.id).collect::<Vec<_>>();
+    assert_eq!(ids(watch.next().await.unwrap().unwrap()), [42]);
+    // The second fetch adds nothing, so the third is the next to yield.
+    assert_eq!(ids(watch.next().await.unwrap().unwrap()), [7]);
+}
+
+#[tokio::test]
+async fn running_out_of_time_is_a_deadline_error() {
+    let server = MockServer::start().await;
+    stub(
+        &server,
+        "/api/cfps",
+        json("[]".to_string()).set_delay(std::time::Duration::from_millis(500)),
+    )
+    .await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .max_elapsed_time(std::time::Duration::from_millis(50))
+        .build();
+
+    match client.get_cfps().await {
+        Err(CFPError::DeadlineExceeded { url, elapsed }) => {
+            assert_eq!(url, format!("{}/api/cfps", server.uri()));
+            assert_eq!(elapsed, std::time::Duration::from_millis(50));
+        }
+        other => panic!("expected a deadline error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn time_budget_applies_without_retries() {
+    let server = MockServer::start().await;
+    stub(
+        &server,
+        "/api/cfps",
+        json("[]".to_string()).set_delay(std::time::Duration::from_millis(500)),
+    )
+    .await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .max_elapsed_time(std::time::Duration::from_millis(50))
+        .disable_retries()
+        .build();
+
+    assert!(matches!(client.get_cfps().await, Err(CFPError::DeadlineExceeded { .. })));
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn watch_with_a_zero_interval_does_not_spin() {
+    use futures::StreamExt;
+
+    let (server, client) = setup().await;
+    stub(&server, "/api/cfps", json(format!("[{}]", RUSTCONF))).await;
+
+    let watch = client.watch(std::time::Duration::ZERO);
+    futures::pin_mut!(watch);
+    assert_eq!(watch.next().await.unwrap().unwrap().len(), 1);
+    // The next fetch waits for the one second minimum.
+    let next = tokio::time::timeout(std::time::Duration::from_millis(500), watch.next()).await;
+    assert!(next.is_err());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn post_is_not_retried_on_a_transient_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/conferences/"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Retries stay enabled, unlike `setup`.
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .token("secret")
+        .build();
+
+    match client.create_conf(cfptime::NewConf::default()).await {
+        Err(CFPError::Api { status, .. }) => assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE),
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn token_is_sent_as_a_bearer_header() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .and(header("authorization", "Bearer sekrit"))
+        .respond_with(json(format!("[{}]", RUSTCONF)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/conferences/"))
+        .and(header("authorization", "Bearer sekrit"))
+        .respond_with(json(EUROCONF.to_string()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let builder = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .token("sekrit")
+        .disable_retries();
+    assert!(!format!("{:?}", builder).contains("sekrit"));
+    let client = builder.build();
+
+    assert_eq!(client.get_cfps().await.unwrap().len(), 1);
+    assert_eq!(client.create_conf(cfptime::NewConf::default()).await.unwrap().id, 7);
+}
+
+#[tokio::test]
+async fn next_cursor_on_another_host_is_not_followed() {
+    let server = MockServer::start().await;
+    let elsewhere = MockServer::start().await;
+    stub(
+        &server,
+        "/api/cfps",
+        json(format!(
+            r#"{{"next": "{}/api/cfps/page/2/", "results": [{}]}}"#,
+            elsewhere.uri(),
+            RUSTCONF
+        )),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .respond_with(json("[]".to_string()))
+        .expect(0)
+        .mount(&elsewhere)
+        .await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .token("sekrit")
+        .disable_retries()
+        .build();
+
+    match client.get_all_cfps_paginated().await {
+        Err(CFPError::UntrustedCursor { url }) => assert_eq!(url, format!("{}/api/cfps/page/2/", elsewhere.uri())),
+        other => panic!("expected an untrusted cursor error, got {:?}", other),
+    }
+}
+
+fn rate_limited(retry_after: &str) -> ResponseTemplate {
+    ResponseTemplate::new(429).insert_header("Retry-After", retry_after)
+}
+
+// A client with retries enabled, unlike `setup`, which the rate-limit
+// handling needs.
+fn retrying_client(server: &MockServer) -> CFPTime {
+    CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .max_retries(2)
+        .build()
+}
+
+#[tokio::test]
+async fn rate_limited_request_waits_for_retry_after_seconds() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(rate_limited("1"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    stub(&server, "/api/cfps", json(format!("[{}]", RUSTCONF))).await;
+
+    let started = std::time::Instant::now();
+    assert_eq!(retrying_client(&server).get_cfps().await.unwrap().len(), 1);
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn rate_limited_request_waits_for_retry_after_date() {
+    let server = MockServer::start().await;
+    let date = (chrono::Utc::now() + chrono::Duration::seconds(3)).format("%a, %d %b %Y %H:%M:%S GMT");
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(rate_limited(&date.to_string()))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    stub(&server, "/api/cfps", json(format!("[{}]", RUSTCONF))).await;
+
+    let started = std::time::Instant::now();
+    assert_eq!(retrying_client(&server).get_cfps().await.unwrap().len(), 1);
+    // The date has a one second resolution.
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn rate_limited_request_fails_once_retries_are_exhausted() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(rate_limited("0"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    match retrying_client(&server).get_cfps().await {
+        Err(CFPError::RateLimited { retry_after, .. }) => assert_eq!(retry_after, std::time::Duration::ZERO),
+        other => panic!("expected a rate limited error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_request_does_not_wait_over_a_minute() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(rate_limited("3600"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    match retrying_client(&server).get_cfps().await {
+        Err(CFPError::RateLimited { retry_after, .. }) => {
+            assert_eq!(retry_after, std::time::Duration::from_secs(3600))
+        }
+        other => panic!("expected a rate limited error, got {:?}", other),
+    }
+}
+
+fn cached_client(server: &MockServer, ttl: std::time::Duration) -> CFPTime {
+    CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .disable_retries()
+        .cache(ttl)
+        .build()
+}
+
+#[tokio::test]
+async fn cached_list_is_fetched_again_after_refresh() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json(format!("[{}]", RUSTCONF)).set_delay(std::time::Duration::from_millis(200)))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let client = cached_client(&server, std::time::Duration::from_secs(60));
+
+    // One request for both concurrent calls, none for the cached one.
+    let other = client.clone();
+    let (first, second) = tokio::join!(client.get_cfps(), other.get_cfps());
+    assert_eq!(first.unwrap().len(), 1);
+    assert_eq!(second.unwrap().len(), 1);
+    assert_eq!(client.get_cfps().await.unwrap().len(), 1);
+
+    client.refresh();
+    assert_eq!(client.get_cfps().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn cached_list_is_fetched_again_once_expired() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .respond_with(json(format!("[{}]", RUSTCONF)))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let client = cached_client(&server, std::time::Duration::from_millis(100));
+
+    client.get_cfps().await.unwrap();
+    client.get_cfps().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    client.get_cfps().await.unwrap();
+}
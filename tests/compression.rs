@@ -0,0 +1,65 @@
+// Compressed responses of `get_cfps`. The fixture holds 100 CFPs in the
+// API's response format, generated since the live API isn't reachable from
+// CI. Run with `--nocapture` to print the sizes quoted in the README.
+use std::io::Write;
+
+use cfptime::CFPTime;
+use wiremock::matchers::{headers, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CFPS: &[u8] = include_bytes!("fixtures/cfps.json");
+
+fn gzip(body: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::new(6));
+    encoder.write_all(body).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn brotli(body: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 5,
+        ..Default::default()
+    };
+    brotli::BrotliCompress(&mut &body[..], &mut compressed, &params).unwrap();
+    compressed
+}
+
+async fn fetch_encoded(encoding: &str, body: Vec<u8>) -> usize {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/cfps"))
+        .and(headers("accept-encoding", vec!["gzip", "br"]))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Encoding", encoding)
+                .set_body_raw(body, "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = CFPTime::builder()
+        .endpoint(format!("{}/api/", server.uri()))
+        .disable_retries()
+        .build();
+
+    client.get_cfps().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn gzip_response_is_decompressed() {
+    let body = gzip(CFPS);
+    println!("raw {} bytes, gzip {} bytes", CFPS.len(), body.len());
+    assert!(body.len() * 5 < CFPS.len());
+
+    assert_eq!(fetch_encoded("gzip", body).await, 100);
+}
+
+#[tokio::test]
+async fn brotli_response_is_decompressed() {
+    let body = brotli(CFPS);
+    println!("raw {} bytes, brotli {} bytes", CFPS.len(), body.len());
+    assert!(body.len() * 5 < CFPS.len());
+
+    assert_eq!(fetch_encoded("br", body).await, 100);
+}
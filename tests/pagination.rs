@@ -0,0 +1,93 @@
+use cfptime::{CFPTime, MockTransport};
+
+fn conf(id: i32) -> String {
+    format!(
+        r#"{{"id": {}, "name": "Conf {}", "cfp_deadline": "", "conf_start_date": "",
+            "country": "", "created_at": "", "number_of_days": 1}}"#,
+        id, id
+    )
+}
+
+#[tokio::test]
+async fn bare_array_is_a_single_page() {
+    let transport = MockTransport::new().with_json("/api/cfps/", format!("[{}, {}]", conf(1), conf(2)));
+    let client = CFPTime::with_transport(transport);
+
+    let page = client.get_cfps_paginated().await.unwrap();
+    assert_eq!(page.count, Some(2));
+    assert_eq!(page.next, None);
+    assert_eq!(page.results.len(), 2);
+}
+
+#[tokio::test]
+async fn envelope_pages_are_followed() {
+    let transport = MockTransport::new()
+        .with_json(
+            "/api/cfps/",
+            format!(
+                r#"{{"count": 3, "next": "https://api.cfptime.org/api/cfps/page/2/", "previous": null, "results": [{}, {}]}}"#,
+                conf(1),
+                conf(2)
+            ),
+        )
+        .with_json(
+            "/api/cfps/page/2/",
+            format!(
+                r#"{{"count": 3, "next": null, "previous": "https://api.cfptime.org/api/cfps/", "results": [{}]}}"#,
+                conf(3)
+            ),
+        );
+    let client = CFPTime::with_transport(transport.clone());
+
+    let first = client.get_cfps_paginated().await.unwrap();
+    assert_eq!(first.count, Some(3));
+    assert_eq!(first.next.as_deref(), Some("https://api.cfptime.org/api/cfps/page/2/"));
+
+    let ids: Vec<i32> = client
+        .get_all_cfps_paginated()
+        .await
+        .unwrap()
+        .iter()
+        .map(|conf| conf.id)
+        .collect();
+    assert_eq!(ids, [1, 2, 3]);
+
+    // `get_cfps` follows the cursors too rather than stopping at the first page.
+    assert_eq!(client.get_cfps().await.unwrap().len(), 3);
+
+    let raw = client.get_cfps_raw().await.unwrap();
+    let ids: Vec<i64> = raw.as_array().unwrap().iter().map(|cfp| cfp["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn cyclic_next_cursor_stops() {
+    let transport = MockTransport::new().with_json(
+        "/api/cfps/",
+        format!(
+            r#"{{"next": "https://api.cfptime.org/api/cfps/", "results": [{}]}}"#,
+            conf(1)
+        ),
+    );
+    let client = CFPTime::with_transport(transport.clone());
+
+    assert_eq!(client.get_all_cfps_paginated().await.unwrap().len(), 1);
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn every_list_method_follows_the_cursors() {
+    let transport = MockTransport::new()
+        .with_json(
+            "/api/conferences",
+            format!(
+                r#"{{"next": "https://api.cfptime.org/api/conferences/page/2/", "results": [{}]}}"#,
+                conf(1)
+            ),
+        )
+        .with_json("/api/conferences/page/2/", format!(r#"{{"next": null, "results": [{}]}}"#, conf(2)));
+    let client = CFPTime::with_transport(transport);
+
+    let ids: Vec<i32> = client.get_confs().await.unwrap().iter().map(|conf| conf.id).collect();
+    assert_eq!(ids, [1, 2]);
+}
@@ -0,0 +1,89 @@
+use cfptime::{CFPTime, MockTransport};
+
+// Send `get_cfps` and `get_cfp(5)` through a client for `endpoint` and return
+// the URLs they resolved to.
+async fn resolved_urls(endpoint: &str) -> Vec<String> {
+    let transport = MockTransport::new();
+    let client = CFPTime::builder()
+        .endpoint(endpoint)
+        .transport(transport.clone())
+        .build();
+
+    let _ = client.get_cfps().await;
+    let _ = client.get_cfp(5).await;
+
+    transport.requests()
+}
+
+#[tokio::test]
+async fn endpoint_with_trailing_slash() {
+    assert_eq!(
+        resolved_urls("https://example.com/api/").await,
+        ["https://example.com/api/cfps", "https://example.com/api/cfps/5/"]
+    );
+}
+
+#[tokio::test]
+async fn endpoint_without_trailing_slash_keeps_last_segment() {
+    assert_eq!(
+        resolved_urls("https://example.com/api").await,
+        ["https://example.com/api/cfps", "https://example.com/api/cfps/5/"]
+    );
+}
+
+#[tokio::test]
+async fn endpoint_with_extra_slashes() {
+    assert_eq!(
+        resolved_urls("https://example.com//api//").await,
+        ["https://example.com/api/cfps", "https://example.com/api/cfps/5/"]
+    );
+}
+
+#[tokio::test]
+async fn endpoint_at_the_root() {
+    assert_eq!(
+        resolved_urls("https://example.com").await,
+        ["https://example.com/cfps", "https://example.com/cfps/5/"]
+    );
+}
+
+#[tokio::test]
+async fn get_cfp_has_no_double_slash() {
+    let urls = resolved_urls("https://api.cfptime.org/api/").await;
+
+    assert_eq!(urls[1], "https://api.cfptime.org/api/cfps/5/");
+    assert!(!urls[1].contains("cfps//"));
+}
+
+#[tokio::test]
+async fn ping_hits_the_endpoint_itself() {
+    let transport = MockTransport::new();
+    let client = CFPTime::builder()
+        .endpoint("https://example.com/api/")
+        .transport(transport.clone())
+        .build();
+
+    let _ = client.ping().await;
+
+    assert_eq!(transport.requests(), ["https://example.com/api/"]);
+}
+
+#[tokio::test]
+async fn id_getters_resolve_exact_urls() {
+    let transport = MockTransport::new()
+        .with_json("/api/cfps/5/", r#"{"id": 5, "name": "A", "cfp_deadline": "", "conf_start_date": "",
+            "country": "", "created_at": "", "number_of_days": 1}"#)
+        .with_json("/api/conferences/7/", r#"{"id": 7, "name": "B", "cfp_deadline": "", "conf_start_date": "",
+            "country": "", "created_at": "", "number_of_days": 1}"#);
+    let client = CFPTime::with_transport(transport.clone());
+
+    assert_eq!(client.get_cfp(5).await.unwrap().id, 5);
+    assert_eq!(client.get_conf(7).await.unwrap().id, 7);
+    assert_eq!(
+        transport.requests(),
+        [
+            "https://api.cfptime.org/api/cfps/5/",
+            "https://api.cfptime.org/api/conferences/7/",
+        ]
+    );
+}